@@ -0,0 +1,568 @@
+use std::f32::consts::TAU;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadState};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use rand::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::config::GameConfig;
+use crate::game::{Enemy, EnemySpawnTimer, Food, FoodSpawnTimer, Health, Score};
+use crate::highscore::RunStats;
+use crate::obstacle::{spawn_obstacle, ArenaWalls, Obstacle, ObstacleError};
+use crate::rng::GameRng;
+use crate::rope::{Rope, RopeBuilder, RopeError, RopeStyle};
+
+const DEFAULT_ARENA: &str = "arenas/default.arena.ron";
+
+// Everything that describes a level: geometry, spawning rules and the starting rope
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct Arena {
+    #[serde(default)]
+    pub walls: Option<Rect>,
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    pub enemy_spawn: SpawnZone,
+    pub food_spawn: SpawnZone,
+    // Enemies further than this from every rope head are despawned
+    pub cull_distance: f32,
+    pub waves: Vec<Wave>,
+    pub rope: RopeDef,
+    pub food: FoodTable,
+}
+
+// Spawns happen between these distances from a rope head
+#[derive(Deserialize, Clone, Debug)]
+pub struct SpawnZone {
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+// Optional values fall back to the GameConfig defaults
+#[derive(Deserialize, Clone, Debug)]
+pub struct Wave {
+    // Seconds since the run started at which this wave takes over
+    pub start: f32,
+    #[serde(default)]
+    pub interval: Option<f32>,
+    #[serde(default)]
+    pub acceleration: Option<f32>,
+    pub min_radius: f32,
+    pub max_radius: f32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RopeDef {
+    pub length: f32,
+    pub count: usize,
+    pub health: i32,
+    #[serde(default)]
+    pub style: RopeStyle,
+}
+
+impl RopeDef {
+    // Laid out straight from the origin
+    pub fn builder(&self) -> RopeBuilder {
+        RopeBuilder::straight(Vec2::ZERO, Vec2::new(self.length, 0.0)).points(self.count)
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct FoodTable {
+    #[serde(default)]
+    pub interval: Option<f32>,
+    pub kinds: Vec<FoodKind>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct FoodKind {
+    #[serde(default)]
+    pub radius: Option<f32>,
+    pub value: i32,
+    pub weight: f32,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Arena {
+            walls: None,
+            obstacles: Vec::new(),
+            enemy_spawn: SpawnZone {
+                min_distance: 100.0,
+                max_distance: 200.0,
+            },
+            food_spawn: SpawnZone {
+                min_distance: 100.0,
+                max_distance: 300.0,
+            },
+            cull_distance: 500.0,
+            waves: vec![Wave {
+                start: 0.0,
+                interval: None,
+                acceleration: None,
+                min_radius: 5.0,
+                max_radius: 15.0,
+            }],
+            rope: RopeDef {
+                length: 50.0,
+                count: 5,
+                health: 5,
+                style: RopeStyle::default(),
+            },
+            food: FoodTable {
+                interval: None,
+                kinds: vec![FoodKind {
+                    radius: None,
+                    value: 1,
+                    weight: 1.0,
+                }],
+            },
+        }
+    }
+}
+
+// Data an arena file can hold but the game cannot run with
+#[derive(Debug, Error, PartialEq)]
+pub enum ArenaError {
+    #[error("{0} spawn distances must satisfy 0 <= min_distance <= max_distance")]
+    SpawnDistance(&'static str),
+    #[error("cull distance must be positive")]
+    CullDistance,
+    #[error("wave {0}: radii must satisfy 0 < min_radius <= max_radius")]
+    WaveRadius(usize),
+    #[error("wave {0}: spawn interval must be positive")]
+    WaveInterval(usize),
+    #[error("wave {0}: waves must be listed by start time")]
+    WaveOrder(usize),
+    #[error("food spawn interval must be positive")]
+    FoodInterval,
+    #[error("food table needs at least one kind with a positive weight")]
+    EmptyFoodTable,
+    #[error("food kind {0}: radius must be positive and weight non-negative")]
+    FoodKind(usize),
+    #[error("obstacle {0}: {1}")]
+    Obstacle(usize, ObstacleError),
+    #[error("walls must be finite with min <= max")]
+    Walls,
+    #[error("rope: {0}")]
+    Rope(RopeError),
+    #[error("rope length must be positive")]
+    RopeLength,
+    #[error("rope health must be positive")]
+    RopeHealth,
+}
+
+impl Arena {
+    pub fn from_ron(bytes: &[u8]) -> Result<Arena, ArenaLoaderError> {
+        let arena = ron::de::from_bytes::<Arena>(bytes)?;
+        arena.validate()?;
+        Ok(arena)
+    }
+
+    // Checks everything spawning and collision rely on, so hand-edited data is
+    // rejected at load rather than panicking in play
+    pub fn validate(&self) -> Result<(), ArenaError> {
+        // Both fail for NaN
+        let positive = |value: f32| value > 0.0 && value.is_finite();
+        let ordered = |min: f32, max: f32| min <= max && max.is_finite();
+        for (name, zone) in [("enemy", &self.enemy_spawn), ("food", &self.food_spawn)] {
+            if !(zone.min_distance >= 0.0 && ordered(zone.min_distance, zone.max_distance)) {
+                return Err(ArenaError::SpawnDistance(name));
+            }
+        }
+        if !positive(self.cull_distance) {
+            return Err(ArenaError::CullDistance);
+        }
+        let mut last_start = f32::NEG_INFINITY;
+        for (i, wave) in self.waves.iter().enumerate() {
+            if !(positive(wave.min_radius) && ordered(wave.min_radius, wave.max_radius)) {
+                return Err(ArenaError::WaveRadius(i));
+            }
+            if !wave.interval.is_none_or(positive) {
+                return Err(ArenaError::WaveInterval(i));
+            }
+            if !ordered(last_start, wave.start) {
+                return Err(ArenaError::WaveOrder(i));
+            }
+            last_start = wave.start;
+        }
+        if !self.food.interval.is_none_or(positive) {
+            return Err(ArenaError::FoodInterval);
+        }
+        for (i, kind) in self.food.kinds.iter().enumerate() {
+            if !(kind.radius.is_none_or(positive) && ordered(0.0, kind.weight)) {
+                return Err(ArenaError::FoodKind(i));
+            }
+        }
+        if !self.food.kinds.iter().any(|kind| kind.weight > 0.0) {
+            return Err(ArenaError::EmptyFoodTable);
+        }
+        for (i, obstacle) in self.obstacles.iter().enumerate() {
+            obstacle
+                .validate()
+                .map_err(|err| ArenaError::Obstacle(i, err))?;
+        }
+        if let Some(walls) = self.walls {
+            let finite = walls.min.is_finite() && walls.max.is_finite();
+            if !(finite && walls.min.cmple(walls.max).all()) {
+                return Err(ArenaError::Walls);
+            }
+        }
+        if !positive(self.rope.length) {
+            return Err(ArenaError::RopeLength);
+        }
+        // Only the rope's own shape is checked here; config tuning is checked with the config
+        self.rope
+            .builder()
+            .build(&GameConfig::default())
+            .map_err(ArenaError::Rope)?;
+        if self.rope.health <= 0 {
+            return Err(ArenaError::RopeHealth);
+        }
+        Ok(())
+    }
+
+    // The last wave whose start time has passed
+    pub fn wave_at(&self, elapsed: f32) -> Option<(usize, &Wave)> {
+        self.waves
+            .iter()
+            .enumerate()
+            .rfind(|(_, wave)| wave.start <= elapsed)
+    }
+}
+
+impl SpawnZone {
+    // Offset from the rope head in a random direction
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec2 {
+        let angle = rng.gen_range(0.0..TAU);
+        let distance = rng.gen_range(self.min_distance..=self.max_distance);
+        Vec2::from_angle(angle) * distance
+    }
+}
+
+impl FoodTable {
+    pub fn choose(&self, rng: &mut impl Rng) -> Option<&FoodKind> {
+        self.kinds.choose_weighted(rng, |kind| kind.weight).ok()
+    }
+}
+
+// The arena currently in play; starts as the built-in default until the asset loads
+#[derive(Resource, Default)]
+pub struct ActiveArena(pub Arena);
+
+#[derive(Resource)]
+pub struct ArenaHandle(Handle<Arena>);
+
+// Tracks run time so the wave table can be followed
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct WaveState {
+    pub elapsed: f32,
+    pub current: Option<usize>,
+}
+
+#[derive(Default)]
+pub struct ArenaLoader;
+
+#[derive(Debug, Error)]
+pub enum ArenaLoaderError {
+    #[error("could not read arena: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse arena: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("invalid arena: {0}")]
+    Invalid(#[from] ArenaError),
+}
+
+impl AssetLoader for ArenaLoader {
+    type Asset = Arena;
+    type Settings = ();
+    type Error = ArenaLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Arena, ArenaLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Arena::from_ron(&bytes)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["arena.ron"]
+    }
+}
+
+// Systems that must observe a freshly applied arena run after this set
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApplyArenaSet;
+
+// Starts a run from the active arena; part of the simulation
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveArena>()
+            .init_resource::<WaveState>()
+            .register_type::<WaveState>()
+            .register_type::<Option<usize>>()
+            .add_systems(
+                Update,
+                apply_arena
+                    .in_set(ApplyArenaSet)
+                    .run_if(resource_changed::<ActiveArena>),
+            );
+    }
+}
+
+// Loads the arena from assets and hot reloads it; needs the AssetPlugin
+pub struct ArenaAssetPlugin;
+
+impl Plugin for ArenaAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Arena>()
+            .init_asset_loader::<ArenaLoader>()
+            .add_systems(Startup, load_arena)
+            .add_systems(Update, activate_loaded_arena.before(ApplyArenaSet));
+    }
+}
+
+fn load_arena(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ArenaHandle(asset_server.load(DEFAULT_ARENA)));
+}
+
+// Copies the arena asset into play when it finishes loading or is hot reloaded
+fn activate_loaded_arena(
+    mut events: EventReader<AssetEvent<Arena>>,
+    handle: Option<Res<ArenaHandle>>,
+    arenas: Res<Assets<Arena>>,
+    mut active: ResMut<ActiveArena>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0) {
+            if let Some(arena) = arenas.get(&handle.0) {
+                active.0 = arena.clone();
+            }
+        }
+    }
+}
+
+// Run condition: the arena asset, if one is loading, has loaded or failed, so no
+// later arena switch will restart the run
+pub fn arena_settled(
+    handle: Option<Res<ArenaHandle>>,
+    asset_server: Option<Res<AssetServer>>,
+) -> bool {
+    match (handle, asset_server) {
+        (Some(handle), Some(asset_server)) => matches!(
+            asset_server.load_state(&handle.0),
+            LoadState::Loaded | LoadState::Failed
+        ),
+        _ => true,
+    }
+}
+
+// Everything that belongs to a run and is rebuilt when an arena is applied
+type RunEntities = Or<(With<Rope>, With<Enemy>, With<Food>, With<Obstacle>)>;
+
+// Restarts the run with the active arena's geometry, rope and spawn rules
+#[allow(clippy::too_many_arguments)]
+fn apply_arena(
+    mut commands: Commands,
+    arena: Res<ActiveArena>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    mut wave_state: ResMut<WaveState>,
+    mut enemy_timer: ResMut<EnemySpawnTimer>,
+    mut food_timer: ResMut<FoodSpawnTimer>,
+    run_stats: Option<ResMut<RunStats>>,
+    previous: Query<Entity, RunEntities>,
+) {
+    let arena = &arena.0;
+    for entity in previous.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for obstacle in arena.obstacles.iter() {
+        spawn_obstacle(&mut commands, obstacle.clone());
+    }
+    match arena.walls {
+        Some(bounds) => commands.insert_resource(ArenaWalls { bounds }),
+        None => commands.remove_resource::<ArenaWalls>(),
+    }
+
+    let rope = arena.rope.builder().build(&config);
+    match rope {
+        Ok(rope) => {
+            commands.spawn((
+                rope,
+                arena.rope.style.clone(),
+                Score { value: 0 },
+                Health::new(arena.rope.health),
+            ));
+        }
+        Err(err) => error!("arena rope is invalid: {err}"),
+    }
+
+    // Every run of an arena replays the same random sequence for the seed
+    rng.reseed();
+    *wave_state = WaveState::default();
+    if let Some((_, wave)) = arena.wave_at(0.0) {
+        let interval = wave.interval.unwrap_or(config.enemy_spawn_interval);
+        enemy_timer.0 = Timer::from_seconds(interval, TimerMode::Repeating);
+    }
+    let interval = arena.food.interval.unwrap_or(config.food_spawn_interval);
+    food_timer.0 = Timer::from_seconds(interval, TimerMode::Repeating);
+    // Tallied by HighScorePlugin when it is added
+    if let Some(mut run_stats) = run_stats {
+        *run_stats = RunStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_arena_asset_parses() {
+        let text = include_str!("../assets/arenas/default.arena.ron");
+        let arena: Arena = ron::from_str(text).unwrap();
+        assert!(!arena.waves.is_empty());
+        assert!(!arena.food.kinds.is_empty());
+        assert_eq!(arena.rope.style.taper, 0.5);
+        assert_eq!(arena.validate(), Ok(()));
+        assert_eq!(Arena::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn malformed_arenas_are_rejected() {
+        let check = |edit: fn(&mut Arena), expected: ArenaError| {
+            let mut arena = Arena::default();
+            edit(&mut arena);
+            assert_eq!(arena.validate(), Err(expected));
+        };
+        check(
+            |arena| arena.enemy_spawn.min_distance = 300.0,
+            ArenaError::SpawnDistance("enemy"),
+        );
+        check(
+            |arena| arena.food_spawn.min_distance = -1.0,
+            ArenaError::SpawnDistance("food"),
+        );
+        check(|arena| arena.cull_distance = 0.0, ArenaError::CullDistance);
+        check(
+            |arena| arena.waves[0].min_radius = 20.0,
+            ArenaError::WaveRadius(0),
+        );
+        check(
+            |arena| arena.waves[0].interval = Some(0.0),
+            ArenaError::WaveInterval(0),
+        );
+        check(
+            |arena| {
+                let mut wave = arena.waves[0].clone();
+                wave.start = -1.0;
+                arena.waves.push(wave);
+            },
+            ArenaError::WaveOrder(1),
+        );
+        check(
+            |arena| arena.food.interval = Some(-2.0),
+            ArenaError::FoodInterval,
+        );
+        check(|arena| arena.food.kinds.clear(), ArenaError::EmptyFoodTable);
+        check(
+            |arena| arena.food.kinds[0].weight = 0.0,
+            ArenaError::EmptyFoodTable,
+        );
+        check(
+            |arena| arena.food.kinds[0].radius = Some(0.0),
+            ArenaError::FoodKind(0),
+        );
+        check(
+            |arena| {
+                arena
+                    .obstacles
+                    .push(Obstacle::polygon(Vec2::ZERO, vec![Vec2::ZERO]))
+            },
+            ArenaError::Obstacle(0, ObstacleError::TooFewPoints(1)),
+        );
+        check(
+            |arena| {
+                arena.walls = Some(Rect {
+                    min: Vec2::new(100.0, 0.0),
+                    max: Vec2::new(0.0, 100.0),
+                })
+            },
+            ArenaError::Walls,
+        );
+        check(
+            |arena| {
+                arena.walls = Some(Rect {
+                    min: Vec2::ZERO,
+                    max: Vec2::new(f32::INFINITY, 100.0),
+                })
+            },
+            ArenaError::Walls,
+        );
+        check(
+            |arena| arena.rope.count = 1,
+            ArenaError::Rope(RopeError::TooFewPoints(1)),
+        );
+        check(|arena| arena.rope.length = -50.0, ArenaError::RopeLength);
+        check(
+            |arena| arena.rope.count = 100_000,
+            ArenaError::Rope(RopeError::TooManyPoints(100_000.0)),
+        );
+        check(|arena| arena.rope.health = 0, ArenaError::RopeHealth);
+    }
+
+    #[test]
+    fn spawn_zone_samples_every_direction_within_its_distances() {
+        let zone = Arena::default().enemy_spawn;
+        let mut rng = GameRng::new(7);
+        let offsets: Vec<Vec2> = (0..200).map(|_| zone.sample(&mut rng.rng)).collect();
+        assert!(offsets.iter().all(|offset| {
+            let distance = offset.length();
+            distance >= zone.min_distance - 1e-3 && distance <= zone.max_distance + 1e-3
+        }));
+        for quadrant in [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+            -Vec2::ONE,
+            Vec2::new(1.0, -1.0),
+        ] {
+            assert!(offsets.iter().any(|offset| offset.signum() == quadrant));
+        }
+    }
+
+    #[test]
+    fn equal_radii_and_distances_are_allowed() {
+        let mut arena = Arena::default();
+        arena.waves[0].max_radius = arena.waves[0].min_radius;
+        arena.enemy_spawn.max_distance = arena.enemy_spawn.min_distance;
+        assert_eq!(arena.validate(), Ok(()));
+    }
+
+    #[test]
+    fn loader_rejects_invalid_arena_files() {
+        let text = include_str!("../assets/arenas/default.arena.ron");
+        assert!(Arena::from_ron(text.as_bytes()).is_ok());
+        let text = text.replace(
+            "min_radius: 5.0, max_radius: 15.0",
+            "min_radius: 15.0, max_radius: 5.0",
+        );
+        assert!(matches!(
+            Arena::from_ron(text.as_bytes()),
+            Err(ArenaLoaderError::Invalid(ArenaError::WaveRadius(0)))
+        ));
+    }
+}
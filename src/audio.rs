@@ -0,0 +1,279 @@
+use bevy::audio::{AddAudioSource, Volume};
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::game::{
+    DetachedRope, EnemyHit, EnemyKilled, FoodCollected, GameOver, RopeDamaged, WaveStarted,
+};
+use crate::rope::Rope;
+use crate::tone::{Note, Tone, Wave};
+use crate::whoosh::WhooshPlugin;
+
+// Rope tip speed, in pixels per tick, at which effects reach their highest pitch
+const TIP_SPEED_FOR_MAX_PITCH: f32 = 40.0;
+const MAX_PITCH: f32 = 1.5;
+
+// Sound effects for the simulation's events and a looping music track. Volumes and
+// mute come from GameConfig; M toggles mute. Browsers keep audio suspended until the
+// page is interacted with (see webbuild/index.html), so on wasm nothing plays before
+// the first click, touch or key press, rather than a burst of queued sounds at once.
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(WhooshPlugin)
+            .add_audio_source::<Tone>()
+            .insert_resource(AudioUnlocked(!cfg!(target_arch = "wasm32")))
+            .add_systems(Startup, setup_sounds)
+            .add_systems(
+                Update,
+                (
+                    unlock_audio.run_if(|unlocked: Res<AudioUnlocked>| !unlocked.0),
+                    toggle_mute,
+                    start_music.run_if(|unlocked: Res<AudioUnlocked>| unlocked.0),
+                    update_music_volume.run_if(resource_changed::<GameConfig>),
+                    play_sound_effects,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct AudioUnlocked(pub bool);
+
+#[derive(Resource)]
+struct Sounds {
+    food: Handle<Tone>,
+    enemy_hit: Handle<Tone>,
+    enemy_kill: Handle<Tone>,
+    damage: Handle<Tone>,
+    wave: Handle<Tone>,
+    game_over: Handle<Tone>,
+    music: Handle<Tone>,
+}
+
+#[derive(Component)]
+struct Music;
+
+// Arpeggios of A minor, F, G and E major, a bar each
+const MUSIC_NOTES: [f32; 16] = [
+    110.0, 164.81, 220.0, 164.81, 87.31, 130.81, 174.61, 130.81, 98.0, 146.83, 196.0, 146.83,
+    82.41, 123.47, 164.81, 123.47,
+];
+const MUSIC_NOTE_SECONDS: f32 = 0.25;
+
+// Every sound is synthesized, so there are no audio files to ship or fail to load
+fn setup_sounds(mut commands: Commands, mut tones: ResMut<Assets<Tone>>) {
+    let arpeggio = |notes: &[f32], seconds: f32| {
+        notes
+            .iter()
+            .map(|hz| Note::new(Wave::Triangle, *hz, seconds))
+            .collect()
+    };
+    commands.insert_resource(Sounds {
+        food: tones.add(Tone::new(
+            0.5,
+            vec![
+                Note::new(Wave::Sine, 660.0, 0.06),
+                Note::new(Wave::Sine, 990.0, 0.1),
+            ],
+        )),
+        enemy_hit: tones.add(Tone::new(
+            0.25,
+            vec![Note::glide(Wave::Square, 220.0, 110.0, 0.08)],
+        )),
+        enemy_kill: tones.add(Tone::new(
+            0.5,
+            vec![Note::glide(Wave::Triangle, 880.0, 220.0, 0.25)],
+        )),
+        damage: tones.add(Tone::new(
+            0.3,
+            vec![Note::glide(Wave::Square, 150.0, 80.0, 0.3)],
+        )),
+        wave: tones.add(Tone::new(0.5, arpeggio(&[523.25, 659.25, 783.99], 0.12))),
+        game_over: tones.add(Tone::new(
+            0.5,
+            arpeggio(&[392.0, 311.13, 261.63, 196.0], 0.3),
+        )),
+        music: tones.add(Tone::new(0.4, arpeggio(&MUSIC_NOTES, MUSIC_NOTE_SECONDS))),
+    });
+}
+
+fn unlock_audio(
+    mut unlocked: ResMut<AudioUnlocked>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+) {
+    if keys.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || touches.any_just_pressed()
+    {
+        unlocked.0 = true;
+    }
+}
+
+fn toggle_mute(keys: Res<ButtonInput<KeyCode>>, mut config: ResMut<GameConfig>) {
+    if keys.just_pressed(KeyCode::KeyM) {
+        config.muted = !config.muted;
+    }
+}
+
+fn music_volume(config: &GameConfig) -> f32 {
+    if config.muted {
+        0.0
+    } else {
+        config.music_volume
+    }
+}
+
+fn start_music(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    tones: Res<Assets<Tone>>,
+    config: Res<GameConfig>,
+    music: Query<(), With<Music>>,
+) {
+    if !music.is_empty() || !tones.contains(&sounds.music) {
+        return;
+    }
+    commands.spawn((
+        AudioSourceBundle {
+            source: sounds.music.clone(),
+            settings: PlaybackSettings::LOOP.with_volume(Volume::new(music_volume(&config))),
+        },
+        Music,
+    ));
+}
+
+fn update_music_volume(config: Res<GameConfig>, music: Query<&AudioSink, With<Music>>) {
+    for sink in music.iter() {
+        sink.set_volume(music_volume(&config));
+    }
+}
+
+// Faster whips play the same effect higher
+fn pitch_for_speed(speed: f32) -> f32 {
+    1.0 + (MAX_PITCH - 1.0) * (speed / TIP_SPEED_FOR_MAX_PITCH).clamp(0.0, 1.0)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn play_sound_effects(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    tones: Res<Assets<Tone>>,
+    config: Res<GameConfig>,
+    unlocked: Res<AudioUnlocked>,
+    ropes: Query<&Rope, Without<DetachedRope>>,
+    mut food: EventReader<FoodCollected>,
+    mut hits: EventReader<EnemyHit>,
+    mut kills: EventReader<EnemyKilled>,
+    mut damage: EventReader<RopeDamaged>,
+    mut waves: EventReader<WaveStarted>,
+    mut game_over: EventReader<GameOver>,
+) {
+    let rope_pitch = |rope: Entity| pitch_for_speed(ropes.get(rope).map_or(0.0, Rope::tail_speed));
+    // Collect everything so the readers are drained even while silent
+    let mut effects: Vec<(Handle<Tone>, f32)> = Vec::new();
+    effects.extend(
+        food.read()
+            .map(|event| (sounds.food.clone(), rope_pitch(event.rope))),
+    );
+    effects.extend(
+        hits.read()
+            .map(|event| (sounds.enemy_hit.clone(), rope_pitch(event.rope))),
+    );
+    effects.extend(
+        kills
+            .read()
+            .map(|event| (sounds.enemy_kill.clone(), rope_pitch(event.rope))),
+    );
+    effects.extend(damage.read().map(|_| (sounds.damage.clone(), 1.0)));
+    effects.extend(waves.read().map(|_| (sounds.wave.clone(), 1.0)));
+    effects.extend(game_over.read().map(|_| (sounds.game_over.clone(), 1.0)));
+    if !unlocked.0 || config.muted {
+        return;
+    }
+    // A player for a missing sound would wait for it forever, and DESPAWN only cleans up
+    // players that finished
+    for (source, pitch) in effects
+        .into_iter()
+        .filter(|(source, _)| tones.contains(source))
+    {
+        commands.spawn(AudioSourceBundle {
+            source,
+            settings: PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(config.sfx_volume))
+                .with_speed(pitch),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Assets<Tone>>()
+            .insert_resource(GameConfig::default())
+            .insert_resource(AudioUnlocked(true))
+            .add_event::<FoodCollected>()
+            .add_event::<EnemyHit>()
+            .add_event::<EnemyKilled>()
+            .add_event::<RopeDamaged>()
+            .add_event::<WaveStarted>()
+            .add_event::<GameOver>()
+            .add_systems(Startup, setup_sounds)
+            .add_systems(Update, (start_music, play_sound_effects));
+        app
+    }
+
+    fn players(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<PlaybackSettings>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn missing_sounds_leave_no_players_behind() {
+        let mut app = audio_app();
+        app.update();
+        assert_eq!(players(&mut app), 1);
+
+        let sounds = app.world.resource::<Sounds>();
+        let (food, music) = (sounds.food.id(), sounds.music.id());
+        let mut tones = app.world.resource_mut::<Assets<Tone>>();
+        tones.remove(food);
+        tones.remove(music);
+        let player = app
+            .world
+            .query_filtered::<Entity, With<Music>>()
+            .single(&app.world);
+        app.world.despawn(player);
+        for _ in 0..10 {
+            app.world.send_event(FoodCollected {
+                rope: Entity::PLACEHOLDER,
+                value: 1,
+                position: Vec2::ZERO,
+            });
+            app.update();
+        }
+        assert_eq!(players(&mut app), 0);
+
+        app.world.send_event(GameOver);
+        app.update();
+        assert_eq!(players(&mut app), 1);
+    }
+
+    #[test]
+    fn pitch_rises_with_tip_speed_up_to_the_cap() {
+        assert_eq!(pitch_for_speed(0.0), 1.0);
+        assert!(pitch_for_speed(10.0) > 1.0);
+        assert!(pitch_for_speed(20.0) > pitch_for_speed(10.0));
+        assert_eq!(pitch_for_speed(TIP_SPEED_FOR_MAX_PITCH), MAX_PITCH);
+        assert_eq!(pitch_for_speed(1000.0), MAX_PITCH);
+    }
+}
@@ -0,0 +1,411 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+const CONFIG_PATH: &str = "config.ron";
+
+// Tuning values used across the simulation. Arenas may override the spawn rates,
+// enemy acceleration and food radius per wave or food kind.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct GameConfig {
+    // Fixed RNG seed; a random one is picked at startup when unset
+    pub seed: Option<u64>,
+    // Native only: capture this run's input to a file, or play one back
+    pub record: Option<String>,
+    pub replay: Option<String>,
+    // Verlet velocity is divided by these each tick
+    pub rope_damping: f32,
+    pub enemy_damping: f32,
+    pub constraint_iterations: usize,
+    // Physics passes per fixed tick: each repeats the rope constraints and the enemy step
+    pub substeps: usize,
+    pub rope_thickness: f32,
+    pub rope_elasticity: f32,
+    pub rope_tear_strain: f32,
+    pub detached_fade_seconds: f32,
+    pub enemy_acceleration: f32,
+    pub enemy_damage: i32,
+    pub enemy_spawn_interval: f32,
+    pub food_spawn_interval: f32,
+    pub food_radius: f32,
+    // Linear volume multipliers; `muted` silences both and can be toggled in game
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub muted: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            seed: None,
+            record: None,
+            replay: None,
+            rope_damping: 1.008,
+            enemy_damping: 1.008,
+            constraint_iterations: 3,
+            substeps: 1,
+            rope_thickness: 6.0,
+            rope_elasticity: 5.0,
+            rope_tear_strain: 8.0,
+            detached_fade_seconds: 1.5,
+            enemy_acceleration: 0.01,
+            enemy_damage: 1,
+            enemy_spawn_interval: 2.0,
+            food_spawn_interval: 1.0,
+            food_radius: 5.0,
+            music_volume: 0.4,
+            sfx_volume: 0.8,
+            muted: false,
+        }
+    }
+}
+
+impl GameConfig {
+    // Reads config.ron from the working directory, falling back to defaults
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> Self {
+        match std::fs::read_to_string(CONFIG_PATH) {
+            Ok(text) => GameConfig::from_ron(&text).unwrap_or_else(|err| {
+                warn!("ignoring {CONFIG_PATH}: {err}");
+                GameConfig::default()
+            }),
+            Err(_) => GameConfig::default(),
+        }
+    }
+
+    // Reads overrides from the page URL, e.g. `?rope_thickness=8&enemy_acceleration=0.02`
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    pub fn load() -> Self {
+        let query = web_sys::window()
+            .and_then(|window| window.location().search().ok())
+            .unwrap_or_default();
+        GameConfig::from_query(&query)
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+    pub fn load() -> Self {
+        GameConfig::default()
+    }
+
+    // A copy without the settings that only apply to the current run, for exporting
+    pub fn tuning(&self) -> Self {
+        let defaults = GameConfig::default();
+        GameConfig {
+            seed: defaults.seed,
+            record: defaults.record,
+            replay: defaults.replay,
+            muted: defaults.muted,
+            ..self.clone()
+        }
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, ron::error::SpannedError> {
+        let mut config: GameConfig = ron::from_str(text)?;
+        config.sanitize();
+        Ok(config)
+    }
+
+    // Puts back the default of every value the simulation cannot run with, such as a
+    // negative spawn interval or a damping that divides by zero, with a warning
+    pub fn sanitize(&mut self) {
+        let defaults = GameConfig::default();
+        let check = |name: &str, value: &mut f32, default: f32, valid: fn(f32) -> bool| {
+            if !(value.is_finite() && valid(*value)) {
+                warn!("ignoring {name} = {value}: out of range");
+                *value = default;
+            }
+        };
+        // Damping below 1 speeds things up every tick
+        check(
+            "rope_damping",
+            &mut self.rope_damping,
+            defaults.rope_damping,
+            |value| value >= 1.0,
+        );
+        check(
+            "enemy_damping",
+            &mut self.enemy_damping,
+            defaults.enemy_damping,
+            |value| value >= 1.0,
+        );
+        check(
+            "rope_thickness",
+            &mut self.rope_thickness,
+            defaults.rope_thickness,
+            |value| value > 0.0,
+        );
+        // Below 2 the constraint solver overshoots, as in the tuning panel
+        check(
+            "rope_elasticity",
+            &mut self.rope_elasticity,
+            defaults.rope_elasticity,
+            |value| value >= 2.0,
+        );
+        check(
+            "rope_tear_strain",
+            &mut self.rope_tear_strain,
+            defaults.rope_tear_strain,
+            |value| value > 0.0,
+        );
+        check(
+            "detached_fade_seconds",
+            &mut self.detached_fade_seconds,
+            defaults.detached_fade_seconds,
+            |value| value >= 0.0,
+        );
+        check(
+            "enemy_acceleration",
+            &mut self.enemy_acceleration,
+            defaults.enemy_acceleration,
+            |value| value >= 0.0,
+        );
+        check(
+            "enemy_spawn_interval",
+            &mut self.enemy_spawn_interval,
+            defaults.enemy_spawn_interval,
+            |value| value > 0.0,
+        );
+        check(
+            "food_spawn_interval",
+            &mut self.food_spawn_interval,
+            defaults.food_spawn_interval,
+            |value| value > 0.0,
+        );
+        check(
+            "food_radius",
+            &mut self.food_radius,
+            defaults.food_radius,
+            |value| value > 0.0,
+        );
+        check(
+            "music_volume",
+            &mut self.music_volume,
+            defaults.music_volume,
+            |value| value >= 0.0,
+        );
+        check(
+            "sfx_volume",
+            &mut self.sfx_volume,
+            defaults.sfx_volume,
+            |value| value >= 0.0,
+        );
+        if self.constraint_iterations == 0 {
+            warn!("ignoring constraint_iterations = 0: out of range");
+            self.constraint_iterations = defaults.constraint_iterations;
+        }
+        if self.substeps == 0 {
+            warn!("ignoring substeps = 0: out of range");
+            self.substeps = defaults.substeps;
+        }
+        if self.enemy_damage < 0 {
+            warn!(
+                "ignoring enemy_damage = {}: out of range",
+                self.enemy_damage
+            );
+            self.enemy_damage = defaults.enemy_damage;
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    // Overwrites config.ron so tuned values are picked up by the next run
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self) -> Result<(), String> {
+        let text = self.to_ron().map_err(|err| err.to_string())?;
+        std::fs::write(CONFIG_PATH, text).map_err(|err| format!("{CONFIG_PATH}: {err}"))
+    }
+
+    // Applies each `key=value` pair on its own; pairs that name no setting or hold a bad
+    // value are skipped with a warning and leave the rest in place
+    #[cfg(feature = "wasm")]
+    pub fn from_query(query: &str) -> Self {
+        let mut config = GameConfig::default();
+        for pair in query.trim_start_matches('?').split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+            let result = match pair.split_once('=') {
+                Some((key, value)) => config.set_from_query(key, value),
+                None => Err("expected key=value".to_string()),
+            };
+            if let Err(err) = result {
+                warn!("ignoring URL config `{pair}`: {err}");
+            }
+        }
+        config.sanitize();
+        config
+    }
+
+    #[cfg(feature = "wasm")]
+    fn set_from_query(&mut self, key: &str, value: &str) -> Result<(), String> {
+        fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String>
+        where
+            T::Err: std::fmt::Display,
+        {
+            value.parse().map_err(|err: T::Err| err.to_string())
+        }
+        let float = |value: &str| {
+            parse::<f32>(value).and_then(|number| {
+                if number.is_finite() {
+                    Ok(number)
+                } else {
+                    Err("not a finite number".to_string())
+                }
+            })
+        };
+        match key {
+            "seed" => self.seed = Some(parse(value)?),
+            "rope_damping" => self.rope_damping = float(value)?,
+            "enemy_damping" => self.enemy_damping = float(value)?,
+            "constraint_iterations" => self.constraint_iterations = parse(value)?,
+            "substeps" => self.substeps = parse(value)?,
+            "rope_thickness" => self.rope_thickness = float(value)?,
+            "rope_elasticity" => self.rope_elasticity = float(value)?,
+            "rope_tear_strain" => self.rope_tear_strain = float(value)?,
+            "detached_fade_seconds" => self.detached_fade_seconds = float(value)?,
+            "enemy_acceleration" => self.enemy_acceleration = float(value)?,
+            "enemy_damage" => self.enemy_damage = parse(value)?,
+            "enemy_spawn_interval" => self.enemy_spawn_interval = float(value)?,
+            "food_spawn_interval" => self.food_spawn_interval = float(value)?,
+            "food_radius" => self.food_radius = float(value)?,
+            "music_volume" => self.music_volume = float(value)?,
+            "sfx_volume" => self.sfx_volume = float(value)?,
+            "muted" => self.muted = parse(value)?,
+            // `record` and `replay` are files, so native only
+            _ => return Err("unknown setting".to_string()),
+        }
+        Ok(())
+    }
+}
+
+// Loads the config during plugin build so it is available to later plugins and
+// resources; add it after DefaultPlugins so load warnings are logged
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameConfig::load());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exported_config_loads_back() {
+        let config = GameConfig {
+            seed: Some(7),
+            rope_elasticity: 3.5,
+            enemy_acceleration: 0.02,
+            ..default()
+        };
+        let loaded: GameConfig = ron::from_str(&config.to_ron().unwrap()).unwrap();
+        assert_eq!(loaded.seed, Some(7));
+        assert_eq!(loaded.rope_elasticity, 3.5);
+        assert_eq!(loaded.enemy_acceleration, 0.02);
+        assert_eq!(loaded.food_radius, config.food_radius);
+    }
+
+    #[test]
+    fn tuning_leaves_out_run_only_settings() {
+        let config = GameConfig {
+            seed: Some(7),
+            record: Some("run.replay".to_string()),
+            replay: Some("other.replay".to_string()),
+            muted: true,
+            rope_elasticity: 3.5,
+            ..default()
+        };
+        let tuning = config.tuning();
+        assert_eq!(tuning.seed, None);
+        assert_eq!(tuning.record, None);
+        assert_eq!(tuning.replay, None);
+        assert!(!tuning.muted);
+        assert_eq!(tuning.rope_elasticity, 3.5);
+    }
+
+    #[test]
+    fn out_of_range_file_values_fall_back_to_defaults() {
+        let config = GameConfig::from_ron(
+            "(enemy_spawn_interval: -1.0, food_spawn_interval: 0.0, detached_fade_seconds: -0.5, \
+             rope_damping: 0.0, rope_elasticity: 0.0, substeps: 0, food_radius: 7.0)",
+        )
+        .unwrap();
+        let defaults = GameConfig::default();
+        assert_eq!(config.enemy_spawn_interval, defaults.enemy_spawn_interval);
+        assert_eq!(config.food_spawn_interval, defaults.food_spawn_interval);
+        assert_eq!(config.detached_fade_seconds, defaults.detached_fade_seconds);
+        assert_eq!(config.rope_damping, defaults.rope_damping);
+        assert_eq!(config.rope_elasticity, defaults.rope_elasticity);
+        assert_eq!(config.substeps, defaults.substeps);
+        assert_eq!(config.food_radius, 7.0);
+    }
+
+    #[test]
+    fn sanitize_keeps_values_on_the_bounds() {
+        let mut config = GameConfig {
+            rope_damping: 1.0,
+            rope_elasticity: 2.0,
+            detached_fade_seconds: 0.0,
+            enemy_acceleration: 0.0,
+            sfx_volume: 0.0,
+            ..default()
+        };
+        config.sanitize();
+        assert_eq!(config.rope_damping, 1.0);
+        assert_eq!(config.rope_elasticity, 2.0);
+        assert_eq!(config.detached_fade_seconds, 0.0);
+        assert_eq!(config.enemy_acceleration, 0.0);
+        assert_eq!(config.sfx_volume, 0.0);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn query_values_out_of_range_fall_back_to_defaults() {
+        let config = GameConfig::from_query(
+            "?enemy_spawn_interval=-1&rope_elasticity=0&rope_damping=0&rope_thickness=9",
+        );
+        let defaults = GameConfig::default();
+        assert_eq!(config.enemy_spawn_interval, defaults.enemy_spawn_interval);
+        assert_eq!(config.rope_elasticity, defaults.rope_elasticity);
+        assert_eq!(config.rope_damping, defaults.rope_damping);
+        assert_eq!(config.rope_thickness, 9.0);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn query_sets_each_listed_field() {
+        let config = GameConfig::from_query("?seed=42&rope_thickness=8&substeps=2&muted=true");
+        assert_eq!(config.seed, Some(42));
+        assert_eq!(config.rope_thickness, 8.0);
+        assert_eq!(config.substeps, 2);
+        assert!(config.muted);
+        assert_eq!(config.food_radius, GameConfig::default().food_radius);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn query_skips_only_the_bad_pairs() {
+        let config = GameConfig::from_query(
+            "?seed=1e5&utm_source=mail&enemy_acceleration=0.02&food_radius=inf&flag&muted=yes",
+        );
+        assert_eq!(config.seed, None);
+        assert_eq!(config.enemy_acceleration, 0.02);
+        assert_eq!(config.food_radius, GameConfig::default().food_radius);
+        assert!(!config.muted);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn malformed_values_keep_the_defaults() {
+        let config = GameConfig::from_query("rope_elasticity=abc&substeps=-1&enemy_damage=");
+        let defaults = GameConfig::default();
+        assert_eq!(config.rope_elasticity, defaults.rope_elasticity);
+        assert_eq!(config.substeps, defaults.substeps);
+        assert_eq!(config.enemy_damage, defaults.enemy_damage);
+    }
+}
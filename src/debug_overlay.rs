@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+
+use crate::arena::ActiveArena;
+use crate::game::{DetachedRope, Enemy, Food, MousePosition};
+use crate::rope::Rope;
+
+// Velocity arrows show where a point would be this many ticks ahead
+const VELOCITY_SCALE: f32 = 10.0;
+// Strain at which a segment of an untearable piece is drawn fully red
+const FREE_PIECE_STRAIN: f32 = 2.0;
+
+// Gizmo view of the physics state, hidden until F3 toggles it on
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DebugOverlay { enabled: false })
+            .add_systems(
+                Update,
+                (
+                    toggle_overlay,
+                    (draw_ropes, draw_enemies, draw_food, draw_spawn_zones)
+                        .run_if(|overlay: Res<DebugOverlay>| overlay.enabled),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+}
+
+fn toggle_overlay(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<DebugOverlay>) {
+    if keys.just_pressed(KeyCode::F3) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+// Green at rest length, red at the tearing point
+fn strain_color(strain: f32, tear_strain: f32) -> Color {
+    let limit = if tear_strain.is_finite() {
+        tear_strain
+    } else {
+        FREE_PIECE_STRAIN
+    };
+    let t = ((strain - 1.0) / (limit - 1.0)).clamp(0.0, 1.0);
+    Color::rgb(t, 1.0 - t, 0.0)
+}
+
+fn draw_ropes(mut gizmos: Gizmos, ropes: Query<&Rope>) {
+    for rope in ropes.iter() {
+        for pair in rope.points.windows(2) {
+            let strain = pair[0].distance(pair[1]) / rope.segment_length;
+            gizmos.line_2d(pair[0], pair[1], strain_color(strain, rope.tear_strain));
+        }
+        for (point, prev) in rope.points.iter().zip(rope.prev_points.iter()) {
+            let velocity = *point - *prev;
+            if velocity != Vec2::ZERO {
+                gizmos.arrow_2d(*point, *point + velocity * VELOCITY_SCALE, Color::CYAN);
+            }
+            gizmos.circle_2d(*point, rope.thickness / 2.0, Color::WHITE);
+        }
+    }
+}
+
+fn draw_enemies(mut gizmos: Gizmos, enemies: Query<&Enemy>, mouse_pos: Res<MousePosition>) {
+    for enemy in enemies.iter() {
+        gizmos.circle_2d(enemy.position, enemy.radius, Color::RED);
+        // Enemies steer towards the cursor
+        gizmos.line_2d(
+            enemy.position,
+            mouse_pos.position,
+            Color::ORANGE.with_a(0.3),
+        );
+        let velocity = enemy.position - enemy.position_prev;
+        if velocity != Vec2::ZERO {
+            gizmos.arrow_2d(
+                enemy.position,
+                enemy.position + velocity * VELOCITY_SCALE,
+                Color::CYAN,
+            );
+        }
+    }
+}
+
+fn draw_food(mut gizmos: Gizmos, food: Query<&Food>) {
+    for piece in food.iter() {
+        gizmos.circle_2d(piece.position, piece.radius, Color::BLUE);
+    }
+}
+
+// Spawn rings and the culling distance around every rope head
+fn draw_spawn_zones(
+    mut gizmos: Gizmos,
+    arena: Res<ActiveArena>,
+    ropes: Query<&Rope, Without<DetachedRope>>,
+) {
+    let arena = &arena.0;
+    for rope in ropes.iter() {
+        let head = rope.points[0];
+        for (zone, color) in [
+            (&arena.enemy_spawn, Color::RED.with_a(0.4)),
+            (&arena.food_spawn, Color::BLUE.with_a(0.4)),
+        ] {
+            gizmos
+                .circle_2d(head, zone.min_distance, color)
+                .segments(64);
+            gizmos
+                .circle_2d(head, zone.max_distance, color)
+                .segments(64);
+        }
+        gizmos
+            .circle_2d(head, arena.cull_distance, Color::GRAY)
+            .segments(96);
+    }
+}
@@ -0,0 +1,705 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::prelude::*;
+
+use crate::arena::{ActiveArena, ArenaPlugin, WaveState};
+use crate::config::GameConfig;
+use crate::obstacle::{obstacle_collisions, ArenaWalls, Obstacle};
+use crate::replay::{play_input, record_input, verify_replay, InputPlayback, InputRecording};
+use crate::rng::{GameRng, RngState};
+use crate::rope::{HeadShape, Rope, RopeStyle};
+#[cfg(debug_assertions)]
+use crate::validate::report_non_finite;
+
+// Overlap, in pixels, past which a rope pushing an enemy is reported as a hit
+const HIT_DEPTH: f32 = 2.0;
+
+// The simulation: ropes, enemies, food and the fixed tick that advances them. It
+// needs no window or renderer, so it runs under MinimalPlugins as well.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .init_resource::<MousePosition>()
+            .init_resource::<EnemySpawnTimer>()
+            .init_resource::<FoodSpawnTimer>()
+            .init_resource::<GameRng>()
+            .add_event::<RopeDamaged>()
+            .add_event::<RopeTorn>()
+            .add_event::<FoodCollected>()
+            .add_event::<EnemyHit>()
+            .add_event::<EnemyKilled>()
+            .add_event::<WaveStarted>()
+            .add_event::<GameOver>()
+            // Reflected so a run can be snapshotted and restored
+            .register_type::<Rope>()
+            .register_type::<RopeStyle>()
+            .register_type::<HeadShape>()
+            .register_type::<DetachedRope>()
+            .register_type::<Enemy>()
+            .register_type::<Food>()
+            .register_type::<MousePosition>()
+            .register_type::<Score>()
+            .register_type::<Health>()
+            .register_type::<EnemySpawnTimer>()
+            .register_type::<FoodSpawnTimer>()
+            .register_type::<RngState>()
+            .register_type::<Vec<Vec2>>()
+            .register_type::<Color>()
+            .register_type::<TimerMode>()
+            .add_plugins(ArenaPlugin)
+            // Ticks run in a fixed order so a seed and input stream always replay the same way
+            .add_systems(
+                FixedUpdate,
+                (
+                    handle_mouse.run_if(not(resource_exists::<InputPlayback>)),
+                    play_input.run_if(resource_exists::<InputPlayback>),
+                    record_input.run_if(resource_exists::<InputRecording>),
+                    spawn_enemies,
+                    spawn_food,
+                    rope_collisions,
+                    rope_rope_collisions,
+                    enemy_collisions,
+                    obstacle_collisions,
+                    update,
+                    fade_detached_ropes,
+                    despawn_dead_ropes,
+                    verify_replay.run_if(resource_exists::<InputPlayback>),
+                )
+                    .chain(),
+            );
+        #[cfg(debug_assertions)]
+        app.add_systems(FixedUpdate, report_non_finite.after(despawn_dead_ropes));
+    }
+}
+
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct MousePosition {
+    pub position: Vec2,
+}
+
+// Marks a piece torn off a rope; it fades out and despawns when the timer ends
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct DetachedRope {
+    pub fade: Timer,
+}
+
+#[derive(Event)]
+pub struct RopeTorn {
+    pub rope: Entity,
+    pub detached: Entity,
+    pub segment: usize,
+}
+
+// Gameplay moments for feedback such as sound; the simulation only sends them
+#[derive(Event)]
+pub struct FoodCollected {
+    pub rope: Entity,
+    pub value: i32,
+    pub position: Vec2,
+}
+
+// A rope body pushed into an enemy hard enough to count as a whip hit
+#[derive(Event)]
+pub struct EnemyHit {
+    pub rope: Entity,
+    pub enemy: Entity,
+    pub position: Vec2,
+}
+
+// An enemy consumed by reaching a rope head
+#[derive(Event)]
+pub struct EnemyKilled {
+    pub rope: Entity,
+    pub enemy: Entity,
+    pub position: Vec2,
+}
+
+#[derive(Event)]
+pub struct WaveStarted {
+    pub index: usize,
+}
+
+// The last rope in play died
+#[derive(Event)]
+pub struct GameOver;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Enemy {
+    pub position: Vec2,
+    pub position_prev: Vec2,
+    pub radius: f32,
+    pub acceleration: f32,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct EnemySpawnTimer(pub Timer);
+
+impl FromWorld for EnemySpawnTimer {
+    fn from_world(world: &mut World) -> Self {
+        let interval = world.resource::<GameConfig>().enemy_spawn_interval;
+        EnemySpawnTimer(Timer::from_seconds(interval, TimerMode::Repeating))
+    }
+}
+
+impl Enemy {
+    pub fn new(position: Vec2, radius: f32, acceleration: f32) -> Self {
+        Enemy {
+            position,
+            position_prev: position,
+            radius,
+            acceleration,
+        }
+    }
+
+    // Substeps repeat the whole step, so enemies also move faster with more of them
+    pub fn update(&mut self, target: Vec2, config: &GameConfig) {
+        for _ in 0..config.substeps.max(1) {
+            let velocity = self.position - self.position_prev;
+            // No pull while sitting exactly on the target
+            let direction_to_target = (target - self.position).normalize_or_zero();
+            let acceleration = direction_to_target * self.acceleration;
+            let next_position = self.position + velocity / config.enemy_damping + acceleration;
+            self.position_prev = self.position;
+            self.position = next_position;
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Food {
+    pub position: Vec2,
+    pub radius: f32,
+    pub value: i32,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct FoodSpawnTimer(pub Timer);
+
+impl FromWorld for FoodSpawnTimer {
+    fn from_world(world: &mut World) -> Self {
+        let interval = world.resource::<GameConfig>().food_spawn_interval;
+        FoodSpawnTimer(Timer::from_seconds(interval, TimerMode::Repeating))
+    }
+}
+
+impl Food {
+    pub fn new(position: Vec2, radius: f32, value: i32) -> Self {
+        Food {
+            position,
+            radius,
+            value,
+        }
+    }
+}
+
+// Score and health are owned by the rope entity they belong to, so every rope
+// in play keeps its own tally.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Score {
+    pub value: i32,
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub value: i32,
+    pub max: i32,
+}
+
+impl Health {
+    pub fn new(max: i32) -> Self {
+        Health { value: max, max }
+    }
+}
+
+#[derive(Event)]
+pub struct RopeDamaged {
+    pub rope: Entity,
+    pub amount: i32,
+}
+
+fn update(
+    mut commands: Commands,
+    mut ropes: Query<(Entity, &mut Rope, Option<&RopeStyle>)>,
+    mut enemies: Query<&mut Enemy>,
+    mouse_pos: Res<MousePosition>,
+    config: Res<GameConfig>,
+    mut torn: EventWriter<RopeTorn>,
+) {
+    let target = mouse_pos.position;
+    for (entity, mut rope, style) in ropes.iter_mut() {
+        let Some(segment) = rope.update(mouse_pos.position, &config) else {
+            continue;
+        };
+        if let Some(piece) = rope.split_off(segment) {
+            // The cut may have moved inwards from the overstretched segment
+            let segment = rope.points.len() - 1;
+            let style = style.cloned().unwrap_or_default().detached();
+            let detached = commands
+                .spawn((
+                    piece,
+                    style,
+                    DetachedRope {
+                        fade: Timer::from_seconds(config.detached_fade_seconds, TimerMode::Once),
+                    },
+                ))
+                .id();
+            torn.send(RopeTorn {
+                rope: entity,
+                detached,
+                segment,
+            });
+        }
+    }
+    for mut enemy in enemies.iter_mut() {
+        enemy.update(target, &config);
+    }
+}
+
+fn handle_mouse(
+    q_windows: Query<&Window, With<PrimaryWindow>>,
+    mut mouse_pos: ResMut<MousePosition>,
+) {
+    if let Ok(window) = q_windows.get_single() {
+        if let Some(cursor_position) = window.cursor_position() {
+            mouse_pos.position = adjust_coords(q_windows, cursor_position);
+        }
+    }
+}
+
+fn adjust_coords(q_windows: Query<&Window, With<PrimaryWindow>>, mouse_pos: Vec2) -> Vec2 {
+    if let Ok(window) = q_windows.get_single() {
+        let window_size = Vec2::new(window.width(), window.height());
+        let adjusted_position = mouse_pos - window_size / 2.0;
+        let adjusted_position = Vec2::new(adjusted_position.x, -adjusted_position.y);
+        return adjusted_position;
+    }
+    Vec2::ZERO
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_enemies(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<EnemySpawnTimer>,
+    arena: Res<ActiveArena>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    mut wave_state: ResMut<WaveState>,
+    ropes: Query<&Rope, Without<DetachedRope>>,
+    enemies: Query<(Entity, &Enemy)>,
+    mut wave_started: EventWriter<WaveStarted>,
+) {
+    let arena = &arena.0;
+    // Rope point 0 of every rope in play
+    let heads: Vec<Vec2> = ropes.iter().map(|rope| rope.points[0]).collect();
+    if heads.is_empty() {
+        return;
+    }
+    // Follow the wave table, switching spawn rate when a new wave starts
+    wave_state.elapsed += time.delta_seconds();
+    let Some((index, wave)) = arena.wave_at(wave_state.elapsed) else {
+        return;
+    };
+    if wave_state.current != Some(index) {
+        wave_state.current = Some(index);
+        let interval = wave.interval.unwrap_or(config.enemy_spawn_interval);
+        timer.0 = Timer::from_seconds(interval, TimerMode::Repeating);
+        wave_started.send(WaveStarted { index });
+    }
+    timer.0.tick(time.delta());
+    if timer.0.finished() {
+        // Spawn around a randomly chosen rope head
+        let rng = &mut rng.rng;
+        let head = heads[rng.gen_range(0..heads.len())];
+
+        // Pick a random direction and a distance inside the arena's enemy spawn zone
+        let position = head + arena.enemy_spawn.sample(rng);
+
+        // Spawn the enemy at the calculated position
+        let radius = rng.gen_range(wave.min_radius..=wave.max_radius);
+        let enemy = commands
+            .spawn(Enemy::new(
+                position,
+                radius,
+                wave.acceleration.unwrap_or(config.enemy_acceleration),
+            ))
+            .id();
+        debug!("enemy {enemy:?} spawned at {position:.0}");
+    }
+    // Despawn enemies that are outside the cull distance of every rope head
+    for (entity, enemy) in enemies.iter() {
+        let out_of_range = heads
+            .iter()
+            .all(|head| enemy.position.distance(*head) > arena.cull_distance);
+        if out_of_range {
+            commands.entity(entity).despawn();
+            debug!("enemy {entity:?} culled");
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_food(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<FoodSpawnTimer>,
+    arena: Res<ActiveArena>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    mut ropes: Query<(Entity, &Rope, &mut Score)>,
+    food: Query<(Entity, &Food)>,
+    obstacles: Query<&Obstacle>,
+    walls: Option<Res<ArenaWalls>>,
+    mut collected: EventWriter<FoodCollected>,
+) {
+    let arena = &arena.0;
+    if ropes.is_empty() {
+        return;
+    }
+    timer.0.tick(time.delta());
+    if timer.0.finished() {
+        // Spawn around a randomly chosen rope head
+        let rng = &mut rng.rng;
+        let heads: Vec<Vec2> = ropes.iter().map(|(_, rope, _)| rope.points[0]).collect();
+        let head = heads[rng.gen_range(0..heads.len())];
+
+        // Pick a random direction and a distance inside the arena's food spawn zone
+        let position = head + arena.food_spawn.sample(rng);
+
+        // Spawn the food at the calculated position unless it lands inside level geometry
+        if let Some(kind) = arena.food.choose(rng) {
+            let radius = kind.radius.unwrap_or(config.food_radius);
+            let piece = Food::new(position, radius, kind.value);
+            let blocked = obstacles
+                .iter()
+                .any(|obstacle| obstacle.contains(piece.position, piece.radius))
+                || walls
+                    .as_ref()
+                    .is_some_and(|walls| !walls.contains(piece.position, piece.radius));
+            if !blocked {
+                let piece = commands.spawn(piece).id();
+                debug!("food {piece:?} spawned at {position:.0}");
+            }
+        }
+    }
+    // Food touched by any point of a rope is collected by that rope
+    for (entity, piece) in food.iter() {
+        for (rope_entity, rope, mut score) in ropes.iter_mut() {
+            let touching = rope
+                .points
+                .iter()
+                .any(|point| piece.position.distance(*point) < rope.thickness + piece.radius);
+            if touching {
+                score.value += piece.value;
+                collected.send(FoodCollected {
+                    rope: rope_entity,
+                    value: piece.value,
+                    position: piece.position,
+                });
+                commands.entity(entity).despawn();
+                debug!("food {entity:?} collected");
+                break;
+            }
+        }
+    }
+}
+
+fn rope_collisions(
+    mut commands: Commands,
+    mut ropes: Query<(Entity, &Rope, &mut Health)>,
+    mut enemies: Query<(Entity, &mut Enemy)>,
+    config: Res<GameConfig>,
+    mut damaged: EventWriter<RopeDamaged>,
+    mut hits: EventWriter<EnemyHit>,
+    mut killed: EventWriter<EnemyKilled>,
+) {
+    let mut consumed: Vec<Entity> = Vec::new();
+    // An enemy pressed against several points is still one hit
+    let mut hit: Vec<Entity> = Vec::new();
+    for (rope_entity, rope, mut health) in ropes.iter_mut() {
+        for (i, point) in rope.points.iter().enumerate() {
+            for (enemy_entity, mut enemy) in enemies.iter_mut() {
+                if consumed.contains(&enemy_entity) {
+                    continue;
+                }
+                let distance = point.distance(enemy.position);
+                let collision_radius = rope.thickness / 2.0 + enemy.radius;
+                if distance < collision_radius {
+                    // An enemy reaching the head hurts the rope and is consumed
+                    if i == 0 {
+                        health.value -= config.enemy_damage;
+                        damaged.send(RopeDamaged {
+                            rope: rope_entity,
+                            amount: config.enemy_damage,
+                        });
+                        killed.send(EnemyKilled {
+                            rope: rope_entity,
+                            enemy: enemy_entity,
+                            position: enemy.position,
+                        });
+                        commands.entity(enemy_entity).despawn();
+                        consumed.push(enemy_entity);
+                        continue;
+                    }
+
+                    // Calculate the correction vector
+                    let direction = (enemy.position - *point).try_normalize().unwrap_or(Vec2::Y);
+                    let depth = collision_radius - distance;
+                    let correction = direction * depth;
+                    // Enemies drifting into a resting rope only overlap it slightly
+                    if depth > HIT_DEPTH && !hit.contains(&enemy_entity) {
+                        hit.push(enemy_entity);
+                        hits.send(EnemyHit {
+                            rope: rope_entity,
+                            enemy: enemy_entity,
+                            position: *point,
+                        });
+                    }
+
+                    // Apply the correction to the enemy position
+                    enemy.position += correction;
+                }
+            }
+        }
+    }
+}
+
+fn rope_rope_collisions(mut ropes: Query<&mut Rope>) {
+    let mut rope_combinations = ropes.iter_combinations_mut();
+    while let Some([mut rope_a, mut rope_b]) = rope_combinations.fetch_next() {
+        let collision_radius = (rope_a.thickness + rope_b.thickness) / 2.0;
+        // Point 0 is pinned to the cursor, so only the trailing points are pushed
+        for i in 1..rope_a.points.len() {
+            for j in 1..rope_b.points.len() {
+                let delta = rope_a.points[i] - rope_b.points[j];
+                let distance = delta.length();
+                if distance < collision_radius {
+                    let direction = delta.try_normalize().unwrap_or(Vec2::Y);
+                    let correction = direction * (collision_radius - distance);
+                    // The lighter rope gives way more
+                    let share_a = rope_b.mass / (rope_a.mass + rope_b.mass);
+                    rope_a.points[i] += correction * share_a;
+                    rope_b.points[j] -= correction * (1.0 - share_a);
+                }
+            }
+        }
+    }
+}
+
+fn fade_detached_ropes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pieces: Query<(Entity, &mut Rope, &mut DetachedRope)>,
+) {
+    for (entity, mut rope, mut detached) in pieces.iter_mut() {
+        detached.fade.tick(time.delta());
+        if detached.fade.finished() {
+            commands.entity(entity).despawn();
+        } else {
+            let alpha = 1.0 - detached.fade.fraction();
+            rope.color.set_a(alpha);
+        }
+    }
+}
+
+fn despawn_dead_ropes(
+    mut commands: Commands,
+    ropes: Query<(Entity, &Health), With<Rope>>,
+    mut game_over: EventWriter<GameOver>,
+) {
+    let mut dead = 0;
+    for (entity, health) in ropes.iter() {
+        if health.value <= 0 {
+            commands.entity(entity).despawn();
+            dead += 1;
+        }
+    }
+    if dead > 0 && dead == ropes.iter().len() {
+        game_over.send(GameOver);
+    }
+}
+
+fn enemy_collisions(mut enemies: Query<&mut Enemy>) {
+    let mut enemy_combinations = enemies.iter_combinations_mut();
+    while let Some([mut enemy_a, mut enemy_b]) = enemy_combinations.fetch_next() {
+        let distance = enemy_a.position.distance(enemy_b.position);
+        let collision_radius = enemy_a.radius + enemy_b.radius; // Radius of the enemy collision sphere
+        if distance < collision_radius {
+            // Calculate the correction vector
+            let direction = (enemy_a.position - enemy_b.position)
+                .try_normalize()
+                .unwrap_or(Vec2::Y);
+            let correction = direction * (collision_radius - distance) / 2.0;
+
+            // Apply the correction to both enemy positions
+            enemy_a.position += correction;
+            enemy_b.position -= correction;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rope::RopeBuilder;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn straight_rope(count: usize) -> Rope {
+        RopeBuilder::straight(Vec2::ZERO, Vec2::new(10.0 * (count as f32 - 1.0), 0.0))
+            .points(count)
+            .build(&GameConfig::default())
+            .unwrap()
+    }
+
+    // A world holding just the resources the collision systems read
+    fn collision_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(GameConfig::default());
+        world.init_resource::<Events<RopeDamaged>>();
+        world.init_resource::<Events<EnemyHit>>();
+        world.init_resource::<Events<EnemyKilled>>();
+        world
+    }
+
+    #[test]
+    fn enemy_accelerates_towards_target() {
+        let config = GameConfig::default();
+        let mut enemy = Enemy::new(Vec2::new(100.0, 0.0), 10.0, 0.5);
+        let mut previous_distance = enemy.position.length();
+        for _ in 0..20 {
+            enemy.update(Vec2::ZERO, &config);
+            let distance = enemy.position.length();
+            assert!(distance < previous_distance);
+            previous_distance = distance;
+        }
+        let velocity = enemy.position - enemy.position_prev;
+        assert!(velocity.x < 0.0);
+        assert!(velocity.y.abs() < 1e-4);
+    }
+
+    #[test]
+    fn enemy_keeps_momentum_past_target() {
+        let config = GameConfig::default();
+        let mut enemy = Enemy::new(Vec2::new(1.0, 0.0), 10.0, 0.0);
+        enemy.position_prev = Vec2::new(3.0, 0.0);
+        enemy.update(Vec2::ZERO, &config);
+        assert!(enemy.position.x < -0.9);
+    }
+
+    #[test]
+    fn rope_collisions_push_enemy_off_trailing_points() {
+        let mut world = collision_world();
+        let rope = straight_rope(5);
+        let collision_radius = rope.thickness / 2.0 + 5.0;
+        world.spawn((rope, Health::new(5)));
+        let enemy = world.spawn(Enemy::new(Vec2::new(20.0, 2.0), 5.0, 0.0)).id();
+
+        world.run_system_once(rope_collisions);
+
+        let position = world.get::<Enemy>(enemy).unwrap().position;
+        assert!(position.distance(Vec2::new(20.0, 0.0)) >= collision_radius - 1e-3);
+        assert!(position.y > 2.0);
+    }
+
+    #[test]
+    fn rope_collisions_send_one_hit_per_enemy() {
+        let mut world = collision_world();
+        world.spawn((straight_rope(5), Health::new(5)));
+        // Deep enough into two neighbouring points to count as a hit on both
+        world.spawn(Enemy::new(Vec2::new(15.0, 1.0), 9.0, 0.0));
+
+        world.run_system_once(rope_collisions);
+
+        let events = world.resource::<Events<EnemyHit>>();
+        assert_eq!(events.get_reader().read(events).count(), 1);
+    }
+
+    #[test]
+    fn rope_collisions_consume_enemy_at_head() {
+        let mut world = collision_world();
+        let rope = world.spawn((straight_rope(5), Health::new(5))).id();
+        let enemy = world.spawn(Enemy::new(Vec2::new(1.0, 1.0), 5.0, 0.0)).id();
+
+        world.run_system_once(rope_collisions);
+
+        assert!(world.get_entity(enemy).is_none());
+        let damage = GameConfig::default().enemy_damage;
+        assert_eq!(world.get::<Health>(rope).unwrap().value, 5 - damage);
+        let events = world.resource::<Events<RopeDamaged>>();
+        let mut reader = events.get_reader();
+        let sent: Vec<_> = reader.read(events).collect();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].rope, rope);
+    }
+
+    #[test]
+    fn enemy_collisions_separate_overlapping_enemies() {
+        let mut world = collision_world();
+        let a = world.spawn(Enemy::new(Vec2::new(0.0, 0.0), 10.0, 0.0)).id();
+        let b = world.spawn(Enemy::new(Vec2::new(12.0, 0.0), 8.0, 0.0)).id();
+
+        world.run_system_once(enemy_collisions);
+
+        let a = world.get::<Enemy>(a).unwrap().position;
+        let b = world.get::<Enemy>(b).unwrap().position;
+        assert!((a.distance(b) - 18.0).abs() < 1e-3);
+        // Both enemies move by the same amount
+        assert!((a.x + 3.0).abs() < 1e-3);
+        assert!((b.x - 15.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn enemy_collisions_ignore_separated_enemies() {
+        let mut world = collision_world();
+        let a = world.spawn(Enemy::new(Vec2::new(0.0, 0.0), 5.0, 0.0)).id();
+        world.spawn(Enemy::new(Vec2::new(30.0, 0.0), 5.0, 0.0));
+
+        world.run_system_once(enemy_collisions);
+
+        assert_eq!(world.get::<Enemy>(a).unwrap().position, Vec2::ZERO);
+    }
+
+    #[test]
+    fn enemy_on_target_stays_finite() {
+        let mut enemy = Enemy::new(Vec2::new(3.0, 4.0), 10.0, 0.5);
+        enemy.update(Vec2::new(3.0, 4.0), &GameConfig::default());
+        assert_eq!(enemy.position, Vec2::new(3.0, 4.0));
+    }
+
+    #[test]
+    fn rope_collisions_handle_enemy_on_a_point() {
+        let mut world = collision_world();
+        world.spawn((straight_rope(5), Health::new(5)));
+        let enemy = world.spawn(Enemy::new(Vec2::new(20.0, 0.0), 5.0, 0.0)).id();
+
+        world.run_system_once(rope_collisions);
+
+        let position = world.get::<Enemy>(enemy).unwrap().position;
+        assert!(position.is_finite());
+        assert_ne!(position, Vec2::new(20.0, 0.0));
+    }
+
+    #[test]
+    fn enemy_collisions_separate_coincident_enemies() {
+        let mut world = collision_world();
+        let a = world.spawn(Enemy::new(Vec2::ZERO, 5.0, 0.0)).id();
+        let b = world.spawn(Enemy::new(Vec2::ZERO, 5.0, 0.0)).id();
+
+        world.run_system_once(enemy_collisions);
+
+        let a = world.get::<Enemy>(a).unwrap().position;
+        let b = world.get::<Enemy>(b).unwrap().position;
+        assert!(a.is_finite() && b.is_finite());
+        assert!((a.distance(b) - 10.0).abs() < 1e-3);
+    }
+}
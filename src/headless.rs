@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::game::MousePosition;
+
+// Steps time manually so each `App::update` advances the simulation by exactly one
+// fixed tick. Use with MinimalPlugins and GamePlugin to run without a window.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            Time::<Fixed>::default().timestep(),
+        ));
+    }
+}
+
+pub fn step_ticks(app: &mut App, ticks: usize) {
+    // The very first update only starts the clock and applies the arena
+    if app.world.resource::<Time<Real>>().first_update().is_none() {
+        app.update();
+    }
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+pub fn set_cursor(app: &mut App, position: Vec2) {
+    app.world.resource_mut::<MousePosition>().position = position;
+}
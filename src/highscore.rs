@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::WaveState;
+use crate::game::{EnemyKilled, FoodCollected, GameOver, Score};
+use crate::storage::{platform_storage, TextStorage};
+
+#[derive(Resource)]
+pub struct HighScoreStorage(pub Box<dyn TextStorage>);
+
+impl Default for HighScoreStorage {
+    fn default() -> Self {
+        HighScoreStorage(platform_storage("scores"))
+    }
+}
+
+// Bests and totals over every run played
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct HighScores {
+    pub best_score: i32,
+    // Seconds
+    pub longest_survival: f32,
+    pub runs: u32,
+    pub food_collected: u32,
+    pub enemies_killed: u32,
+}
+
+impl HighScores {
+    pub fn load(storage: &dyn TextStorage) -> Self {
+        let Some(text) = storage.load() else {
+            return HighScores::default();
+        };
+        ron::from_str(&text).unwrap_or_else(|err| {
+            warn!("ignoring stored high scores: {err}");
+            HighScores::default()
+        })
+    }
+
+    pub fn save(&self, storage: &dyn TextStorage) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())?;
+        storage.save(&text)
+    }
+
+    pub fn record(&mut self, run: &RunStats) {
+        self.best_score = self.best_score.max(run.score);
+        self.longest_survival = self.longest_survival.max(run.survival);
+        self.runs += 1;
+        self.food_collected += run.food_collected;
+        self.enemies_killed += run.enemies_killed;
+    }
+}
+
+// Tally of the run in progress; reflected so it is kept in run snapshots
+#[derive(Resource, Reflect, Default, Clone, Debug)]
+#[reflect(Resource)]
+pub struct RunStats {
+    pub score: i32,
+    pub survival: f32,
+    pub food_collected: u32,
+    pub enemies_killed: u32,
+}
+
+// Loads high scores at startup and records every run when it ends. Insert a
+// HighScoreStorage before adding it to store them somewhere else.
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScoreStorage>()
+            .init_resource::<RunStats>()
+            .register_type::<RunStats>()
+            .add_systems(Startup, load_high_scores)
+            .add_systems(Update, (track_run, record_run).chain());
+    }
+}
+
+fn load_high_scores(mut commands: Commands, storage: Res<HighScoreStorage>) {
+    commands.insert_resource(HighScores::load(storage.0.as_ref()));
+}
+
+// Starting a run resets the tally (see apply_arena), while a rope spawned into a run in
+// progress, such as one restored from a snapshot, carries on with it
+fn track_run(
+    mut run: ResMut<RunStats>,
+    scores: Query<&Score>,
+    wave_state: Res<WaveState>,
+    mut food: EventReader<FoodCollected>,
+    mut kills: EventReader<EnemyKilled>,
+) {
+    run.food_collected += food.read().count() as u32;
+    run.enemies_killed += kills.read().count() as u32;
+    // The scoring rope is gone by the time the game is over, so keep the last values
+    if let Some(score) = scores.iter().map(|score| score.value).max() {
+        run.score = run.score.max(score);
+        run.survival = wave_state.elapsed;
+    }
+}
+
+fn record_run(
+    mut game_over: EventReader<GameOver>,
+    run: Res<RunStats>,
+    storage: Res<HighScoreStorage>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    if game_over.read().count() == 0 {
+        return;
+    }
+    high_scores.record(&run);
+    if let Err(err) = high_scores.save(storage.0.as_ref()) {
+        error!("could not save high scores: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn run(score: i32, survival: f32) -> RunStats {
+        RunStats {
+            score,
+            survival,
+            food_collected: 2,
+            enemies_killed: 1,
+        }
+    }
+
+    #[test]
+    fn record_keeps_bests_and_adds_totals() {
+        let mut scores = HighScores::default();
+        scores.record(&run(10, 30.0));
+        scores.record(&run(4, 45.0));
+        assert_eq!(scores.best_score, 10);
+        assert_eq!(scores.longest_survival, 45.0);
+        assert_eq!(scores.runs, 2);
+        assert_eq!(scores.food_collected, 4);
+        assert_eq!(scores.enemies_killed, 2);
+    }
+
+    #[test]
+    fn scores_round_trip_through_storage() {
+        let storage = MemoryStorage::default();
+        assert_eq!(HighScores::load(&storage), HighScores::default());
+        let mut scores = HighScores::default();
+        scores.record(&run(7, 12.5));
+        scores.save(&storage).unwrap();
+        assert_eq!(HighScores::load(&storage), scores);
+    }
+
+    #[test]
+    fn unreadable_scores_start_fresh() {
+        let storage = MemoryStorage::default();
+        storage.save("not ron").unwrap();
+        assert_eq!(HighScores::load(&storage), HighScores::default());
+    }
+}
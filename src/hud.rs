@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::game::{Health, Score};
+use crate::highscore::HighScores;
+use crate::rng::GameRng;
+use crate::rope::Rope;
+
+// Score, health and seed text; works with any renderer's camera
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_hud)
+            .add_systems(Update, (update_score_text, update_seed_text));
+    }
+}
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[derive(Component)]
+pub struct SeedText;
+
+fn setup_hud(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text {
+                sections: vec![TextSection {
+                    value: "Score: 0".to_string(),
+                    style: TextStyle {
+                        font_size: 40.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ScoreText,
+    ));
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::GRAY,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(5.0),
+            left: Val::Px(5.0),
+            ..default()
+        }),
+        SeedText,
+    ));
+}
+
+fn update_score_text(
+    ropes: Query<(&Score, &Health), With<Rope>>,
+    high_scores: Option<Res<HighScores>>,
+    mut texts: Query<&mut Text, With<ScoreText>>,
+) {
+    let mut lines: Vec<String> = ropes
+        .iter()
+        .enumerate()
+        .map(|(i, (score, health))| {
+            format!(
+                "P{} Score: {}  Health: {}/{}",
+                i + 1,
+                score.value,
+                health.value,
+                health.max
+            )
+        })
+        .collect();
+    // Stored bests, once HighScorePlugin has loaded them; MenuPlugin lists the rest
+    if let Some(high_scores) = high_scores {
+        lines.push(format!("Best: {}", high_scores.best_score));
+    }
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+fn update_seed_text(rng: Res<GameRng>, mut texts: Query<&mut Text, With<SeedText>>) {
+    if !rng.is_changed() {
+        return;
+    }
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("Seed: {}", rng.seed);
+    }
+}
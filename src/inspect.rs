@@ -0,0 +1,288 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::snapshot::run_scene;
+
+// Entities listed in the panel; the rest are counted
+const MAX_ENTITIES: usize = 30;
+// Characters of each component shown in the panel
+const MAX_VALUE_LENGTH: usize = 100;
+
+// Reflection-based view of the run for debug builds, toggled with F6: every run entity
+// with its reflected components, marked when they changed since the previous frame
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inspector>()
+            .add_systems(Startup, setup_inspector_text)
+            .add_systems(
+                Update,
+                (
+                    toggle_inspector,
+                    update_inspector.run_if(|inspector: Res<Inspector>| inspector.enabled),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// What changed between two scenes of the same world
+#[derive(Debug, PartialEq)]
+pub enum SceneChange {
+    Spawned(Entity),
+    Despawned(Entity),
+    // A component added, removed or with a different value; `None` for a resource
+    Changed {
+        entity: Option<Entity>,
+        type_path: String,
+    },
+}
+
+impl fmt::Display for SceneChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneChange::Spawned(entity) => write!(f, "{entity:?} spawned"),
+            SceneChange::Despawned(entity) => write!(f, "{entity:?} despawned"),
+            SceneChange::Changed {
+                entity: Some(entity),
+                type_path,
+            } => write!(f, "{entity:?} {type_path} changed"),
+            SceneChange::Changed {
+                entity: None,
+                type_path,
+            } => write!(f, "{type_path} changed"),
+        }
+    }
+}
+
+fn type_path(value: &dyn Reflect) -> &str {
+    value
+        .get_represented_type_info()
+        .map_or(value.reflect_type_path(), |info| info.type_path())
+}
+
+// Components or resources of `after` that are new or differ from `before`, then the
+// ones that are gone
+fn diff_values(
+    entity: Option<Entity>,
+    before: &[Box<dyn Reflect>],
+    after: &[Box<dyn Reflect>],
+    changes: &mut Vec<SceneChange>,
+) {
+    let find = |values: &[Box<dyn Reflect>], path: &str| {
+        values
+            .iter()
+            .position(|value| type_path(value.as_ref()) == path)
+    };
+    for value in after {
+        let path = type_path(value.as_ref());
+        let unchanged = find(before, path)
+            .is_some_and(|index| before[index].reflect_partial_eq(value.as_ref()) == Some(true));
+        if !unchanged {
+            changes.push(SceneChange::Changed {
+                entity,
+                type_path: path.to_string(),
+            });
+        }
+    }
+    for value in before {
+        let path = type_path(value.as_ref());
+        if find(after, path).is_none() {
+            changes.push(SceneChange::Changed {
+                entity,
+                type_path: path.to_string(),
+            });
+        }
+    }
+}
+
+// Compares two scenes extracted from the same world, entity by entity
+pub fn diff_scenes(before: &DynamicScene, after: &DynamicScene) -> Vec<SceneChange> {
+    let mut changes = Vec::new();
+    diff_values(None, &before.resources, &after.resources, &mut changes);
+    for entity in after.entities.iter() {
+        match before.entities.iter().find(|e| e.entity == entity.entity) {
+            Some(previous) => diff_values(
+                Some(entity.entity),
+                &previous.components,
+                &entity.components,
+                &mut changes,
+            ),
+            None => changes.push(SceneChange::Spawned(entity.entity)),
+        }
+    }
+    for entity in before.entities.iter() {
+        if !after.entities.iter().any(|e| e.entity == entity.entity) {
+            changes.push(SceneChange::Despawned(entity.entity));
+        }
+    }
+    changes
+}
+
+#[derive(Resource, Default)]
+struct Inspector {
+    enabled: bool,
+    previous: Option<DynamicScene>,
+}
+
+#[derive(Component)]
+struct InspectorText;
+
+fn setup_inspector_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 14.0,
+                color: Color::LIME_GREEN,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            // Above the seed text
+            bottom: Val::Px(30.0),
+            left: Val::Px(5.0),
+            ..default()
+        }),
+        InspectorText,
+    ));
+}
+
+fn toggle_inspector(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut inspector: ResMut<Inspector>,
+    mut texts: Query<&mut Text, With<InspectorText>>,
+) {
+    if !keys.just_pressed(KeyCode::F6) {
+        return;
+    }
+    inspector.enabled = !inspector.enabled;
+    if !inspector.enabled {
+        inspector.previous = None;
+        for mut text in texts.iter_mut() {
+            text.sections[0].value.clear();
+        }
+    }
+}
+
+fn short_value(value: &dyn Reflect) -> String {
+    let text = format!("{value:?}");
+    match text.char_indices().nth(MAX_VALUE_LENGTH) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
+
+fn update_inspector(world: &mut World) {
+    let scene = run_scene(world);
+    let previous = world.resource_mut::<Inspector>().previous.take();
+    let changes = previous
+        .as_ref()
+        .map(|previous| diff_scenes(previous, &scene))
+        .unwrap_or_default();
+    let changed = |entity: Option<Entity>, path: &str| {
+        changes.iter().any(|change| match change {
+            SceneChange::Spawned(e) => entity == Some(*e),
+            SceneChange::Changed {
+                entity: e,
+                type_path,
+            } => *e == entity && type_path == path,
+            SceneChange::Despawned(_) => false,
+        })
+    };
+    let mark = |changed: bool| if changed { "*" } else { " " };
+
+    let mut lines = vec![format!(
+        "Inspector (F6), * changed since last frame: {} changes",
+        changes.len()
+    )];
+    for resource in scene.resources.iter() {
+        let path = type_path(resource.as_ref());
+        lines.push(format!(
+            "{} {}",
+            mark(changed(None, path)),
+            short_value(resource.as_ref())
+        ));
+    }
+    for entity in scene.entities.iter().take(MAX_ENTITIES) {
+        lines.push(format!("{:?}", entity.entity));
+        for component in entity.components.iter() {
+            let path = type_path(component.as_ref());
+            lines.push(format!(
+                "  {} {}",
+                mark(changed(Some(entity.entity), path)),
+                short_value(component.as_ref())
+            ));
+        }
+    }
+    if scene.entities.len() > MAX_ENTITIES {
+        lines.push(format!(
+            "... {} more entities",
+            scene.entities.len() - MAX_ENTITIES
+        ));
+    }
+    for change in changes
+        .iter()
+        .filter(|change| matches!(change, SceneChange::Despawned(_)))
+    {
+        lines.push(change.to_string());
+    }
+
+    let text = lines.join("\n");
+    let mut texts = world.query_filtered::<&mut Text, With<InspectorText>>();
+    for mut panel in texts.iter_mut(world) {
+        panel.sections[0].value.clone_from(&text);
+    }
+    world.resource_mut::<Inspector>().previous = Some(scene);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Enemy, Food};
+
+    fn scene(world: &mut World) -> DynamicScene {
+        let entities: Vec<Entity> = world
+            .query_filtered::<Entity, Or<(With<Enemy>, With<Food>)>>()
+            .iter(world)
+            .collect();
+        DynamicSceneBuilder::from_world(world)
+            .extract_entities(entities.into_iter())
+            .build()
+    }
+
+    #[test]
+    fn diff_reports_spawns_despawns_and_changed_components() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Enemy>();
+            registry.register::<Food>();
+        }
+        world.insert_resource(registry);
+        let enemy = world.spawn(Enemy::new(Vec2::ZERO, 8.0, 0.1)).id();
+        world.spawn(Food::new(Vec2::ONE, 5.0, 1));
+        let eaten = world.spawn(Food::new(Vec2::NEG_ONE, 5.0, 1)).id();
+        let before = scene(&mut world);
+        assert_eq!(diff_scenes(&before, &scene(&mut world)), vec![]);
+
+        world.get_mut::<Enemy>(enemy).unwrap().position = Vec2::X;
+        world.despawn(eaten);
+        let spawned = world.spawn(Food::new(Vec2::Y, 5.0, 1)).id();
+        assert_eq!(
+            diff_scenes(&before, &scene(&mut world)),
+            vec![
+                SceneChange::Changed {
+                    entity: Some(enemy),
+                    type_path: "web_game::game::Enemy".to_string(),
+                },
+                SceneChange::Spawned(spawned),
+                SceneChange::Despawned(eaten),
+            ]
+        );
+    }
+}
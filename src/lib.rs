@@ -0,0 +1,38 @@
+pub mod arena;
+pub mod audio;
+pub mod config;
+#[cfg(feature = "debug-overlay")]
+pub mod debug_overlay;
+pub mod game;
+pub mod headless;
+pub mod highscore;
+pub mod hud;
+pub mod inspect;
+pub mod menu;
+#[cfg(feature = "render-mesh")]
+pub mod mesh;
+pub mod obstacle;
+pub mod particles;
+pub mod pause;
+#[cfg(feature = "perf-hud")]
+pub mod perf_hud;
+#[cfg(feature = "render-polyline")]
+pub mod polyline;
+pub mod render;
+pub mod replay;
+pub mod rng;
+pub mod rope;
+#[cfg(feature = "render-mesh")]
+pub mod rope_mesh;
+#[cfg(feature = "render-lyon")]
+pub mod shapes;
+pub mod snapshot;
+pub mod storage;
+pub mod tone;
+#[cfg(feature = "tuning-panel")]
+pub mod tuning;
+pub mod validate;
+pub mod whoosh;
+
+pub use game::GamePlugin;
+pub use render::RenderPlugin;
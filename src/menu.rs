@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+
+use crate::arena::ActiveArena;
+use crate::game::{GameOver, Health};
+use crate::highscore::{HighScores, RunStats};
+use crate::pause::Pause;
+use crate::rope::Rope;
+
+// Title screen shown at startup and again when a run ends, with the stored high scores.
+// It holds the pause until a click or Space, which continues the run in play or starts a
+// new one. Needs PausePlugin.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunEnded>()
+            .add_systems(Startup, (open_menu, setup_menu_text))
+            .add_systems(
+                Update,
+                (open_menu_on_game_over, close_menu, update_menu_text).chain(),
+            );
+    }
+}
+
+// Whether the menu is showing the end of a run rather than the title
+#[derive(Resource, Default)]
+struct RunEnded(bool);
+
+#[derive(Component)]
+struct MenuText;
+
+fn open_menu(mut pause: ResMut<Pause>) {
+    pause.menu = true;
+}
+
+fn setup_menu_text(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+                MenuText,
+            ));
+        });
+}
+
+fn open_menu_on_game_over(
+    mut game_over: EventReader<GameOver>,
+    mut pause: ResMut<Pause>,
+    mut ended: ResMut<RunEnded>,
+) {
+    if game_over.read().count() > 0 {
+        pause.menu = true;
+        ended.0 = true;
+    }
+}
+
+fn close_menu(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut pause: ResMut<Pause>,
+    mut ended: ResMut<RunEnded>,
+    mut arena: ResMut<ActiveArena>,
+    in_play: Query<(), (With<Rope>, With<Health>)>,
+) {
+    if !pause.menu || !(keys.just_pressed(KeyCode::Space) || mouse.just_pressed(MouseButton::Left))
+    {
+        return;
+    }
+    pause.menu = false;
+    ended.0 = false;
+    // Applying the arena again starts a new run
+    if in_play.is_empty() {
+        arena.set_changed();
+    }
+}
+
+// Stats of the run that just ended, if any, then the bests and totals
+fn menu_text(run: Option<&RunStats>, high_scores: Option<&HighScores>) -> String {
+    let mut lines = Vec::new();
+    match run {
+        Some(run) => {
+            lines.push("Game Over".to_string());
+            lines.push(format!(
+                "Score: {}  Survived: {:.1}s",
+                run.score, run.survival
+            ));
+            lines.push("Click or press Space to play again".to_string());
+        }
+        None => lines.push("Click or press Space to play".to_string()),
+    }
+    if let Some(high_scores) = high_scores {
+        lines.push(String::new());
+        lines.push(format!("Best score: {}", high_scores.best_score));
+        lines.push(format!("Longest run: {:.1}s", high_scores.longest_survival));
+        lines.push(format!(
+            "Runs: {}  Food: {}  Kills: {}",
+            high_scores.runs, high_scores.food_collected, high_scores.enemies_killed
+        ));
+    }
+    lines.join("\n")
+}
+
+fn update_menu_text(
+    pause: Res<Pause>,
+    ended: Res<RunEnded>,
+    run: Option<Res<RunStats>>,
+    high_scores: Option<Res<HighScores>>,
+    mut texts: Query<&mut Text, With<MenuText>>,
+) {
+    let value = if pause.menu {
+        let run = run.as_deref().filter(|_| ended.0);
+        menu_text(run, high_scores.as_deref())
+    } else {
+        String::new()
+    };
+    for mut text in texts.iter_mut() {
+        text.sections[0].value.clone_from(&value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_lists_the_ended_run_and_high_scores() {
+        let high_scores = HighScores {
+            best_score: 12,
+            longest_survival: 30.5,
+            runs: 3,
+            food_collected: 9,
+            enemies_killed: 4,
+        };
+        let title = menu_text(None, Some(&high_scores));
+        assert!(title.starts_with("Click or press Space to play\n"));
+        assert!(title.contains("Best score: 12"));
+        assert!(title.contains("Longest run: 30.5s"));
+        assert!(title.contains("Runs: 3  Food: 9  Kills: 4"));
+
+        let run = RunStats {
+            score: 7,
+            survival: 12.0,
+            ..default()
+        };
+        let game_over = menu_text(Some(&run), Some(&high_scores));
+        assert!(game_over.starts_with("Game Over\nScore: 7  Survived: 12.0s\n"));
+        assert!(game_over.contains("Best score: 12"));
+
+        assert_eq!(menu_text(None, None), "Click or press Space to play");
+    }
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::NoFrustumCulling;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::game::{Enemy, Food};
+use crate::particles::{ParticlePlugin, Particles};
+use crate::rope_mesh::RopeMeshPlugin;
+
+// Draws ropes as stroke meshes, enemies and food as circles and particles as one
+// mesh of small quads
+pub struct MeshRenderPlugin;
+
+impl Plugin for MeshRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((RopeMeshPlugin, ParticlePlugin))
+            .add_systems(Startup, (setup_particle_mesh, setup_circles))
+            .add_systems(
+                Update,
+                (
+                    (attach_circles, follow_enemies).chain(),
+                    update_particle_mesh,
+                ),
+            );
+    }
+}
+
+#[derive(Component)]
+struct ParticleMesh;
+
+fn setup_particle_mesh(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    write_particle_mesh(&mut mesh, &[]);
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(mesh)),
+            material: materials.add(ColorMaterial::from(Color::WHITE)),
+            // Above the ropes and circles
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
+            ..default()
+        },
+        NoFrustumCulling,
+        ParticleMesh,
+    ));
+}
+
+// Each particle is a square shrinking and fading over its lifetime
+fn write_particle_mesh(mesh: &mut Mesh, particles: &[(Vec2, f32, [f32; 4])]) {
+    let mut positions = Vec::with_capacity(particles.len() * 4);
+    let mut colors = Vec::with_capacity(particles.len() * 4);
+    let mut indices = Vec::with_capacity(particles.len() * 6);
+    for (position, half_size, color) in particles.iter() {
+        let start = positions.len() as u32;
+        for corner in [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::ONE,
+            Vec2::new(-1.0, 1.0),
+        ] {
+            positions.push((*position + corner * *half_size).extend(0.0).to_array());
+            colors.push(*color);
+        }
+        indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+}
+
+fn update_particle_mesh(
+    particles: Res<Particles>,
+    handles: Query<&Mesh2dHandle, With<ParticleMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    if !particles.is_changed() {
+        return;
+    }
+    let quads: Vec<(Vec2, f32, [f32; 4])> = particles
+        .alive()
+        .map(|particle| {
+            let remaining = particle.remaining();
+            let color = particle.color.with_a(particle.color.a() * remaining);
+            (
+                particle.position,
+                particle.size * remaining,
+                color.as_linear_rgba_f32(),
+            )
+        })
+        .collect();
+    for handle in handles.iter() {
+        if let Some(mesh) = meshes.get_mut(&handle.0) {
+            write_particle_mesh(mesh, &quads);
+        }
+    }
+}
+
+// One unit circle and a material per kind, shared by every enemy and piece of food
+#[derive(Resource)]
+struct CircleAssets {
+    mesh: Mesh2dHandle,
+    enemy: Handle<ColorMaterial>,
+    food: Handle<ColorMaterial>,
+}
+
+fn setup_circles(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(CircleAssets {
+        mesh: Mesh2dHandle(meshes.add(Circle { radius: 1.0 })),
+        enemy: materials.add(ColorMaterial::from(Color::RED)),
+        food: materials.add(ColorMaterial::from(Color::BLUE)),
+    });
+}
+
+fn attach_circles(
+    mut commands: Commands,
+    enemies: Query<(Entity, &Enemy), Added<Enemy>>,
+    food: Query<(Entity, &Food), Added<Food>>,
+    circles: Res<CircleAssets>,
+) {
+    let added = enemies
+        .iter()
+        .map(|(entity, enemy)| (entity, enemy.position, enemy.radius, &circles.enemy))
+        .chain(
+            food.iter()
+                .map(|(entity, piece)| (entity, piece.position, piece.radius, &circles.food)),
+        );
+    for (entity, position, radius, material) in added {
+        commands.entity(entity).insert((
+            MaterialMesh2dBundle {
+                mesh: circles.mesh.clone(),
+                material: material.clone(),
+                // The unit circle is scaled up to the radius
+                transform: Transform::from_translation(position.extend(0.0))
+                    .with_scale(Vec3::splat(radius)),
+                ..default()
+            },
+            CircleMesh,
+        ));
+    }
+}
+
+fn follow_enemies(mut enemies: Query<(&Enemy, &mut Transform), With<CircleMesh>>) {
+    for (enemy, mut transform) in enemies.iter_mut() {
+        transform.translation = enemy.position.extend(0.0);
+    }
+}
+
+#[derive(Component)]
+pub struct CircleMesh;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circles_are_attached_once_and_share_their_assets() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<ColorMaterial>()
+            .add_systems(Startup, setup_circles)
+            .add_systems(Update, (attach_circles, follow_enemies).chain());
+        let enemy = app
+            .world
+            .spawn(Enemy::new(Vec2::new(10.0, 0.0), 5.0, 1.0))
+            .id();
+        app.world.spawn(Food::new(Vec2::new(0.0, 20.0), 3.0, 1));
+        app.update();
+
+        app.world.get_mut::<Enemy>(enemy).unwrap().position = Vec2::new(40.0, 0.0);
+        app.update();
+        app.update();
+        assert_eq!(app.world.resource::<Assets<Mesh>>().len(), 1);
+        assert_eq!(app.world.resource::<Assets<ColorMaterial>>().len(), 2);
+        let mut circles = app.world.query_filtered::<&Transform, With<CircleMesh>>();
+        assert_eq!(circles.iter(&app.world).count(), 2);
+        let transform = app.world.get::<Transform>(enemy).unwrap();
+        assert_eq!(transform.translation, Vec3::new(40.0, 0.0, 0.0));
+        assert_eq!(transform.scale, Vec3::splat(5.0));
+    }
+}
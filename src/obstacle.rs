@@ -0,0 +1,348 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::game::Enemy;
+use crate::rope::Rope;
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum ObstacleShape {
+    Circle { radius: f32 },
+    // Axis-aligned box given by half its width and height
+    Box { half_extents: Vec2 },
+    // Convex polygon with counter-clockwise points relative to the obstacle position
+    Polygon { points: Vec<Vec2> },
+}
+
+#[derive(Component, Clone, Debug, Deserialize)]
+pub struct Obstacle {
+    pub position: Vec2,
+    pub shape: ObstacleShape,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ObstacleError {
+    #[error("obstacle size must be positive")]
+    InvalidSize,
+    #[error("a polygon needs at least 3 points, got {0}")]
+    TooFewPoints(usize),
+    #[error("polygon has no area")]
+    DegeneratePolygon,
+    #[error("polygon is not convex")]
+    ConcavePolygon,
+}
+
+// Optional rectangular walls around the play area
+#[derive(Resource)]
+pub struct ArenaWalls {
+    pub bounds: Rect,
+}
+
+impl Obstacle {
+    pub fn polygon(position: Vec2, mut points: Vec<Vec2>) -> Self {
+        // Collision normals assume counter-clockwise winding
+        if signed_area(&points) < 0.0 {
+            points.reverse();
+        }
+        Obstacle {
+            position,
+            shape: ObstacleShape::Polygon { points },
+        }
+    }
+
+    // Collision only works for shapes with a size and convex polygons. Polygons may be
+    // wound either way, as spawn_obstacle rewinds them.
+    pub fn validate(&self) -> Result<(), ObstacleError> {
+        match &self.shape {
+            ObstacleShape::Circle { radius } => {
+                if !(*radius > 0.0 && radius.is_finite()) {
+                    return Err(ObstacleError::InvalidSize);
+                }
+            }
+            ObstacleShape::Box { half_extents } => {
+                if !(half_extents.cmpgt(Vec2::ZERO).all() && half_extents.is_finite()) {
+                    return Err(ObstacleError::InvalidSize);
+                }
+            }
+            ObstacleShape::Polygon { points } => {
+                let count = points.len();
+                if count < 3 {
+                    return Err(ObstacleError::TooFewPoints(count));
+                }
+                let area = signed_area(points);
+                if !(area.abs() > f32::EPSILON && area.is_finite()) {
+                    return Err(ObstacleError::DegeneratePolygon);
+                }
+                // Every corner turns the same way as the winding
+                let concave = (0..count).any(|i| {
+                    let a = points[(i + 1) % count] - points[i];
+                    let b = points[(i + 2) % count] - points[(i + 1) % count];
+                    a.perp_dot(b) * area.signum() < 0.0
+                });
+                if concave {
+                    return Err(ObstacleError::ConcavePolygon);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Returns the vector that moves a circle at `point` with `radius` out of the obstacle
+    pub fn penetration(&self, point: Vec2, radius: f32) -> Option<Vec2> {
+        let local = point - self.position;
+        match &self.shape {
+            ObstacleShape::Circle {
+                radius: obstacle_radius,
+            } => {
+                let distance = local.length();
+                let collision_radius = radius + obstacle_radius;
+                if distance >= collision_radius {
+                    return None;
+                }
+                let direction = if distance > 0.0 {
+                    local / distance
+                } else {
+                    Vec2::Y
+                };
+                Some(direction * (collision_radius - distance))
+            }
+            ObstacleShape::Box { half_extents } => {
+                let closest = local.clamp(-*half_extents, *half_extents);
+                if closest == local {
+                    // Inside the box: leave through the nearest face
+                    let to_face = *half_extents - local.abs();
+                    let sign = Vec2::new(local.x.signum(), local.y.signum());
+                    if to_face.x < to_face.y {
+                        Some(Vec2::new(sign.x * (to_face.x + radius), 0.0))
+                    } else {
+                        Some(Vec2::new(0.0, sign.y * (to_face.y + radius)))
+                    }
+                } else {
+                    push_from_point(local, closest, radius)
+                }
+            }
+            ObstacleShape::Polygon { points } => {
+                let count = points.len();
+                let mut deepest: Option<(f32, Vec2)> = None;
+                for i in 0..count {
+                    let edge = points[(i + 1) % count] - points[i];
+                    // Repeated points leave a zero length edge with no normal
+                    let Some(normal) = Vec2::new(edge.y, -edge.x).try_normalize() else {
+                        continue;
+                    };
+                    let separation = (local - points[i]).dot(normal);
+                    if deepest.is_none_or(|(deepest, _)| separation > deepest) {
+                        deepest = Some((separation, normal));
+                    }
+                }
+                // Without a single edge there is nothing to collide with
+                let (separation, normal) = deepest?;
+                if separation > radius {
+                    return None;
+                }
+                if separation <= 0.0 {
+                    // Inside the polygon: leave through the least penetrated edge
+                    return Some(normal * (radius - separation));
+                }
+                let closest = (0..count)
+                    .map(|i| closest_on_segment(local, points[i], points[(i + 1) % count]))
+                    .min_by(|a, b| {
+                        a.distance_squared(local)
+                            .total_cmp(&b.distance_squared(local))
+                    })?;
+                push_from_point(local, closest, radius)
+            }
+        }
+    }
+
+    pub fn contains(&self, point: Vec2, radius: f32) -> bool {
+        self.penetration(point, radius).is_some()
+    }
+}
+
+// Twice the area, positive for counter-clockwise points
+fn signed_area(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum()
+}
+
+fn push_from_point(point: Vec2, closest: Vec2, radius: f32) -> Option<Vec2> {
+    let delta = point - closest;
+    let distance = delta.length();
+    if distance >= radius || distance == 0.0 {
+        return None;
+    }
+    Some(delta / distance * (radius - distance))
+}
+
+fn closest_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    if ab == Vec2::ZERO {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+impl ArenaWalls {
+    // Returns the position moved back inside the walls, keeping `radius` clearance
+    pub fn confine(&self, point: Vec2, radius: f32) -> Vec2 {
+        let inset = Vec2::splat(radius);
+        point.clamp(self.bounds.min + inset, self.bounds.max - inset)
+    }
+
+    pub fn contains(&self, point: Vec2, radius: f32) -> bool {
+        self.confine(point, radius) == point
+    }
+}
+
+pub fn spawn_obstacle(commands: &mut Commands, obstacle: Obstacle) -> Entity {
+    // Obstacles loaded from data may list polygon points in either winding
+    let obstacle = match obstacle.shape {
+        ObstacleShape::Polygon { points } => Obstacle::polygon(obstacle.position, points),
+        _ => obstacle,
+    };
+    commands.spawn(obstacle).id()
+}
+
+pub fn obstacle_collisions(
+    obstacles: Query<&Obstacle>,
+    walls: Option<Res<ArenaWalls>>,
+    mut ropes: Query<&mut Rope>,
+    mut enemies: Query<&mut Enemy>,
+) {
+    for mut rope in ropes.iter_mut() {
+        let radius = rope.thickness / 2.0;
+        // The pinned head always follows the cursor, so only the trailing points collide
+        let first = if rope.pinned { 1 } else { 0 };
+        for i in first..rope.points.len() {
+            for obstacle in obstacles.iter() {
+                if let Some(correction) = obstacle.penetration(rope.points[i], radius) {
+                    rope.points[i] += correction;
+                }
+            }
+            if let Some(walls) = &walls {
+                rope.points[i] = walls.confine(rope.points[i], radius);
+            }
+        }
+    }
+    for mut enemy in enemies.iter_mut() {
+        let radius = enemy.radius;
+        for obstacle in obstacles.iter() {
+            if let Some(correction) = obstacle.penetration(enemy.position, radius) {
+                enemy.position += correction;
+            }
+        }
+        if let Some(walls) = &walls {
+            enemy.position = walls.confine(enemy.position, radius);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Vec<Vec2> {
+        vec![
+            Vec2::new(-10.0, -10.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(0.0, 10.0),
+        ]
+    }
+
+    #[test]
+    fn circle_pushes_out_along_the_centre_line() {
+        let obstacle = Obstacle {
+            position: Vec2::new(5.0, 0.0),
+            shape: ObstacleShape::Circle { radius: 10.0 },
+        };
+        let push = obstacle.penetration(Vec2::new(13.0, 0.0), 4.0).unwrap();
+        assert!((push - Vec2::new(6.0, 0.0)).length() < 1e-5);
+        assert_eq!(obstacle.penetration(Vec2::new(20.0, 0.0), 4.0), None);
+    }
+
+    #[test]
+    fn box_pushes_out_through_the_nearest_face() {
+        let obstacle = Obstacle {
+            position: Vec2::ZERO,
+            shape: ObstacleShape::Box {
+                half_extents: Vec2::new(20.0, 10.0),
+            },
+        };
+        // Inside, closest to the top face
+        let push = obstacle.penetration(Vec2::new(0.0, 8.0), 1.0).unwrap();
+        assert!((push - Vec2::new(0.0, 3.0)).length() < 1e-5);
+        // Outside, overlapping the right face
+        let push = obstacle.penetration(Vec2::new(22.0, 0.0), 3.0).unwrap();
+        assert!((push - Vec2::new(1.0, 0.0)).length() < 1e-5);
+        assert_eq!(obstacle.penetration(Vec2::new(30.0, 0.0), 3.0), None);
+    }
+
+    #[test]
+    fn polygon_pushes_out_in_either_winding() {
+        let mut clockwise = triangle();
+        clockwise.reverse();
+        for points in [triangle(), clockwise] {
+            let obstacle = Obstacle::polygon(Vec2::ZERO, points);
+            // Inside, just above the bottom edge
+            let push = obstacle.penetration(Vec2::new(0.0, -9.0), 1.0).unwrap();
+            assert!((push - Vec2::new(0.0, -2.0)).length() < 1e-5);
+            // Outside, overlapping the bottom edge
+            let push = obstacle.penetration(Vec2::new(0.0, -11.0), 2.0).unwrap();
+            assert!((push - Vec2::new(0.0, -1.0)).length() < 1e-5);
+            assert_eq!(obstacle.penetration(Vec2::new(0.0, -20.0), 2.0), None);
+        }
+    }
+
+    #[test]
+    fn degenerate_polygon_never_collides() {
+        for points in [
+            vec![],
+            vec![Vec2::ONE],
+            vec![Vec2::ONE, Vec2::ONE, Vec2::ONE],
+        ] {
+            let obstacle = Obstacle {
+                position: Vec2::ZERO,
+                shape: ObstacleShape::Polygon { points },
+            };
+            assert_eq!(obstacle.penetration(Vec2::ONE, 5.0), None);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_unusable_shapes() {
+        let polygon = |points: Vec<Vec2>| Obstacle {
+            position: Vec2::ZERO,
+            shape: ObstacleShape::Polygon { points },
+        };
+        assert_eq!(polygon(triangle()).validate(), Ok(()));
+        let mut clockwise = triangle();
+        clockwise.reverse();
+        assert_eq!(polygon(clockwise).validate(), Ok(()));
+        assert_eq!(
+            polygon(vec![Vec2::ZERO, Vec2::X]).validate(),
+            Err(ObstacleError::TooFewPoints(2))
+        );
+        assert_eq!(
+            polygon(vec![Vec2::ZERO, Vec2::X, Vec2::X * 2.0]).validate(),
+            Err(ObstacleError::DegeneratePolygon)
+        );
+        let arrow = vec![
+            Vec2::new(-10.0, -10.0),
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, -10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        assert_eq!(
+            polygon(arrow).validate(),
+            Err(ObstacleError::ConcavePolygon)
+        );
+        let circle = Obstacle {
+            position: Vec2::ZERO,
+            shape: ObstacleShape::Circle { radius: 0.0 },
+        };
+        assert_eq!(circle.validate(), Err(ObstacleError::InvalidSize));
+    }
+}
@@ -0,0 +1,200 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::{DetachedRope, EnemyHit, EnemyKilled, FoodCollected, RopeDamaged};
+use crate::rng::GameRng;
+use crate::rope::{Rope, RopeStyle};
+
+const CAPACITY: usize = 1024;
+// Velocity is divided by this each tick, like rope and enemy damping but stronger
+const DAMPING: f32 = 1.08;
+// Tail speed, in pixels per tick, above which the rope leaves a trail
+const TRAIL_SPEED: f32 = 12.0;
+
+// Cosmetic particles: bursts for pickups, kills, hits and damage, and a trail behind a
+// fast rope tail. They live in a fixed-size pool stepped with the fixed tick, and use
+// their own RNG so the simulation's random sequence is untouched.
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Particles>()
+            .add_systems(FixedUpdate, (step_particles, emit_trails).chain())
+            .add_systems(Update, emit_bursts);
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Particle {
+    pub position: Vec2,
+    pub prev_position: Vec2,
+    pub color: Color,
+    pub size: f32,
+    pub age: u32,
+    pub lifetime: u32,
+}
+
+impl Particle {
+    pub fn alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+
+    // 1 when emitted, falling to 0 at the end of its lifetime
+    pub fn remaining(&self) -> f32 {
+        1.0 - self.age as f32 / self.lifetime.max(1) as f32
+    }
+}
+
+// Ring buffer of particles; when every slot is busy the oldest particle is replaced
+#[derive(Resource)]
+pub struct Particles {
+    particles: Vec<Particle>,
+    next: usize,
+    rng: ChaCha8Rng,
+}
+
+impl Particles {
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        Particles {
+            particles: vec![Particle::default(); capacity],
+            next: 0,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn emit(&mut self, position: Vec2, velocity: Vec2, color: Color, size: f32, lifetime: u32) {
+        if self.particles.is_empty() {
+            return;
+        }
+        self.particles[self.next] = Particle {
+            position,
+            prev_position: position - velocity,
+            color,
+            size,
+            age: 0,
+            lifetime,
+        };
+        self.next = (self.next + 1) % self.particles.len();
+    }
+
+    // `count` particles flying out of `position` in random directions
+    pub fn burst(&mut self, position: Vec2, count: usize, speed: f32, color: Color, size: f32) {
+        for _ in 0..count {
+            let direction = Vec2::from_angle(self.rng.gen_range(0.0..TAU));
+            let velocity = direction * speed * self.rng.gen_range(0.3..1.0);
+            let lifetime = self.rng.gen_range(20..40);
+            self.emit(position, velocity, color, size, lifetime);
+        }
+    }
+
+    pub fn step(&mut self) {
+        for particle in self.particles.iter_mut().filter(|p| p.alive()) {
+            let velocity = particle.position - particle.prev_position;
+            particle.prev_position = particle.position;
+            particle.position += velocity / DAMPING;
+            particle.age += 1;
+        }
+    }
+
+    pub fn alive(&self) -> impl Iterator<Item = &Particle> {
+        self.particles.iter().filter(|particle| particle.alive())
+    }
+}
+
+impl FromWorld for Particles {
+    fn from_world(world: &mut World) -> Self {
+        let seed = world.get_resource::<GameRng>().map_or(0, |rng| rng.seed);
+        Particles::new(CAPACITY, seed)
+    }
+}
+
+fn step_particles(mut particles: ResMut<Particles>) {
+    particles.step();
+}
+
+fn emit_trails(
+    mut particles: ResMut<Particles>,
+    ropes: Query<(&Rope, Option<&RopeStyle>), Without<DetachedRope>>,
+) {
+    for (rope, style) in ropes.iter() {
+        let (Some(tail), Some(prev)) = (rope.points.last(), rope.prev_points.last()) else {
+            continue;
+        };
+        if rope.tail_speed() < TRAIL_SPEED {
+            continue;
+        }
+        let color = style.map_or(rope.color, |style| style.tail_color);
+        // Trail particles drift behind the tail rather than keep its speed
+        let velocity = (*tail - *prev) * 0.1;
+        particles.emit(*tail, velocity, color.with_a(0.6), rope.thickness / 2.0, 15);
+    }
+}
+
+fn emit_bursts(
+    mut particles: ResMut<Particles>,
+    ropes: Query<&Rope>,
+    mut food: EventReader<FoodCollected>,
+    mut hits: EventReader<EnemyHit>,
+    mut kills: EventReader<EnemyKilled>,
+    mut damage: EventReader<RopeDamaged>,
+) {
+    for event in food.read() {
+        particles.burst(event.position, 12, 3.0, Color::CYAN, 3.0);
+    }
+    for event in hits.read() {
+        particles.burst(event.position, 4, 2.0, Color::WHITE, 2.0);
+    }
+    for event in kills.read() {
+        particles.burst(event.position, 20, 4.0, Color::ORANGE_RED, 3.0);
+    }
+    for event in damage.read() {
+        if let Ok(rope) = ropes.get(event.rope) {
+            particles.burst(rope.points[0], 16, 5.0, Color::RED, 4.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn particles_keep_momentum_and_expire() {
+        let mut particles = Particles::new(4, 1);
+        particles.emit(Vec2::ZERO, Vec2::new(2.0, 0.0), Color::WHITE, 1.0, 3);
+        particles.step();
+        let particle = *particles.alive().next().unwrap();
+        assert!(particle.position.x > 0.0 && particle.position.x < 2.0);
+        assert_eq!(particle.age, 1);
+
+        particles.step();
+        particles.step();
+        assert_eq!(particles.alive().count(), 0);
+    }
+
+    #[test]
+    fn full_pool_replaces_the_oldest_particle() {
+        let mut particles = Particles::new(3, 1);
+        for i in 0..4 {
+            let position = Vec2::new(i as f32, 0.0);
+            particles.emit(position, Vec2::ZERO, Color::WHITE, 1.0, 10);
+        }
+        let mut xs: Vec<f32> = particles.alive().map(|p| p.position.x).collect();
+        xs.sort_by(f32::total_cmp);
+        assert_eq!(xs, vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn burst_emits_around_the_position() {
+        let mut particles = Particles::new(CAPACITY, 7);
+        particles.burst(Vec2::new(10.0, 10.0), 8, 3.0, Color::WHITE, 1.0);
+        assert_eq!(particles.alive().count(), 8);
+        for particle in particles.alive() {
+            assert_eq!(particle.position, Vec2::new(10.0, 10.0));
+            assert!(particle.position.distance(particle.prev_position) <= 3.0);
+        }
+    }
+}
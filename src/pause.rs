@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use bevy::window::{CursorEntered, CursorLeft, WindowFocused, WindowOccluded};
+
+use crate::snapshot::SaveSnapshot;
+
+// Seconds counted down before play continues
+const RESUME_COUNTDOWN: f32 = 3.0;
+// Fixed ticks a single frame may catch up on; time beyond that is dropped
+const MAX_CATCH_UP_TICKS: u32 = 4;
+
+// Pauses the simulation while the window is unfocused, hidden (such as a background
+// browser tab) or the cursor is outside it, and counts down before resuming. The fixed
+// tick is driven by virtual time, so pausing it stops the simulation where it is.
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Pause>()
+            .add_event::<SaveSnapshot>()
+            .add_systems(Startup, (clamp_catch_up, setup_pause_text))
+            .add_systems(
+                Update,
+                (track_pause, apply_pause, update_pause_text).chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct Pause {
+    pub unfocused: bool,
+    pub hidden: bool,
+    pub cursor_outside: bool,
+    // Held while the title or game over menu is open (see MenuPlugin)
+    pub menu: bool,
+    // Seconds left before play resumes once nothing holds the pause
+    pub countdown: f32,
+}
+
+impl Pause {
+    pub fn held(&self) -> bool {
+        self.unfocused || self.hidden || self.cursor_outside || self.menu
+    }
+
+    pub fn running(&self) -> bool {
+        !self.held() && self.countdown <= 0.0
+    }
+
+    // Restarts the countdown while held, otherwise advances it by `seconds`
+    pub fn tick(&mut self, seconds: f32) {
+        if self.held() {
+            self.countdown = RESUME_COUNTDOWN;
+        } else {
+            self.countdown = (self.countdown - seconds).max(0.0);
+        }
+    }
+}
+
+#[derive(Component)]
+struct PauseText;
+
+// After a stall, such as a backgrounded tab that stopped getting frames, the fixed tick
+// would otherwise run many times in one frame and enemies would jump onto the player
+fn clamp_catch_up(mut virtual_time: ResMut<Time<Virtual>>, fixed_time: Res<Time<Fixed>>) {
+    virtual_time.set_max_delta(fixed_time.timestep() * MAX_CATCH_UP_TICKS);
+}
+
+fn setup_pause_text(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                PauseText,
+            ));
+        });
+}
+
+fn track_pause(
+    mut pause: ResMut<Pause>,
+    real_time: Res<Time<Real>>,
+    mut focused: EventReader<WindowFocused>,
+    mut occluded: EventReader<WindowOccluded>,
+    mut left: EventReader<CursorLeft>,
+    mut entered: EventReader<CursorEntered>,
+    mut save: EventWriter<SaveSnapshot>,
+) {
+    let was_running = pause.running();
+    for event in focused.read() {
+        pause.unfocused = !event.focused;
+    }
+    // Sent for document visibility changes in the browser
+    for event in occluded.read() {
+        pause.hidden = event.occluded;
+    }
+    // The cursor position is lost outside the window, so hold the pause until it is back
+    // rather than steer towards where it was last seen
+    if left.read().count() > 0 {
+        pause.cursor_outside = true;
+    }
+    if entered.read().count() > 0 {
+        pause.cursor_outside = false;
+    }
+    pause.tick(real_time.delta_seconds());
+    if was_running && !pause.running() {
+        save.send(SaveSnapshot);
+    }
+}
+
+fn apply_pause(pause: Res<Pause>, mut virtual_time: ResMut<Time<Virtual>>) {
+    if pause.running() {
+        if virtual_time.is_paused() {
+            virtual_time.unpause();
+        }
+    } else if !virtual_time.is_paused() {
+        virtual_time.pause();
+    }
+}
+
+fn update_pause_text(pause: Res<Pause>, mut texts: Query<&mut Text, With<PauseText>>) {
+    // The menu says what to do instead
+    let value = if pause.menu {
+        String::new()
+    } else if pause.held() {
+        "Paused".to_string()
+    } else if pause.countdown > 0.0 {
+        format!("{}", pause.countdown.ceil())
+    } else {
+        String::new()
+    };
+    for mut text in texts.iter_mut() {
+        text.sections[0].value.clone_from(&value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn countdown_starts_once_nothing_holds_the_pause() {
+        let mut pause = Pause::default();
+        assert!(pause.running());
+
+        pause.unfocused = true;
+        pause.cursor_outside = true;
+        pause.tick(10.0);
+        assert!(!pause.running());
+        assert_eq!(pause.countdown, RESUME_COUNTDOWN);
+
+        pause.unfocused = false;
+        pause.tick(1.0);
+        assert_eq!(pause.countdown, RESUME_COUNTDOWN);
+
+        pause.cursor_outside = false;
+        pause.tick(1.0);
+        assert!(!pause.running());
+        pause.tick(RESUME_COUNTDOWN);
+        assert!(pause.running());
+        assert_eq!(pause.countdown, 0.0);
+    }
+}
@@ -0,0 +1,147 @@
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin,
+    RegisterDiagnostic,
+};
+use bevy::prelude::*;
+
+use crate::game::{Enemy, Food};
+use crate::rope::Rope;
+
+pub const FIXED_TICKS: DiagnosticPath = DiagnosticPath::const_new("game/fixed_ticks");
+pub const ENEMY_COUNT: DiagnosticPath = DiagnosticPath::const_new("game/enemies");
+pub const FOOD_COUNT: DiagnosticPath = DiagnosticPath::const_new("game/food");
+pub const ROPE_POINT_COUNT: DiagnosticPath = DiagnosticPath::const_new("game/rope_points");
+pub const MESH_COUNT: DiagnosticPath = DiagnosticPath::const_new("assets/meshes");
+pub const MATERIAL_COUNT: DiagnosticPath = DiagnosticPath::const_new("assets/color_materials");
+
+// Frame timing, simulation and asset counters in the top right corner, toggled with F4
+pub struct PerfHudPlugin;
+
+impl Plugin for PerfHudPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        for path in [
+            FIXED_TICKS,
+            ENEMY_COUNT,
+            FOOD_COUNT,
+            ROPE_POINT_COUNT,
+            MESH_COUNT,
+            MATERIAL_COUNT,
+        ] {
+            app.register_diagnostic(Diagnostic::new(path));
+        }
+        app.init_resource::<FixedTicks>()
+            .add_systems(Startup, setup_perf_text)
+            .add_systems(FixedUpdate, count_fixed_ticks)
+            .add_systems(
+                Update,
+                (record_measurements, toggle_perf_text, update_perf_text).chain(),
+            );
+    }
+}
+
+// Fixed updates run since the last frame
+#[derive(Resource, Default)]
+struct FixedTicks(u32);
+
+#[derive(Component)]
+pub struct PerfText;
+
+fn setup_perf_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::YELLOW,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            right: Val::Px(5.0),
+            ..default()
+        }),
+        PerfText,
+    ));
+}
+
+fn count_fixed_ticks(mut ticks: ResMut<FixedTicks>) {
+    ticks.0 += 1;
+}
+
+fn record_measurements(
+    mut diagnostics: Diagnostics,
+    mut ticks: ResMut<FixedTicks>,
+    enemies: Query<(), With<Enemy>>,
+    food: Query<(), With<Food>>,
+    ropes: Query<&Rope>,
+    meshes: Option<Res<Assets<Mesh>>>,
+    materials: Option<Res<Assets<ColorMaterial>>>,
+) {
+    diagnostics.add_measurement(&FIXED_TICKS, || ticks.0 as f64);
+    ticks.0 = 0;
+    diagnostics.add_measurement(&ENEMY_COUNT, || enemies.iter().count() as f64);
+    diagnostics.add_measurement(&FOOD_COUNT, || food.iter().count() as f64);
+    diagnostics.add_measurement(&ROPE_POINT_COUNT, || {
+        ropes.iter().map(|rope| rope.points.len()).sum::<usize>() as f64
+    });
+    if let Some(meshes) = meshes {
+        diagnostics.add_measurement(&MESH_COUNT, || meshes.len() as f64);
+    }
+    if let Some(materials) = materials {
+        diagnostics.add_measurement(&MATERIAL_COUNT, || materials.len() as f64);
+    }
+}
+
+fn toggle_perf_text(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut texts: Query<&mut Visibility, With<PerfText>>,
+) {
+    if !keys.just_pressed(KeyCode::F4) {
+        return;
+    }
+    for mut visibility in texts.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn update_perf_text(
+    store: Res<DiagnosticsStore>,
+    mut texts: Query<(&mut Text, &Visibility), With<PerfText>>,
+) {
+    let value = |path: &DiagnosticPath| {
+        store
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(0.0)
+    };
+    let count = |path: &DiagnosticPath| {
+        store
+            .get(path)
+            .and_then(|diagnostic| diagnostic.value())
+            .unwrap_or(0.0)
+    };
+    for (mut text, visibility) in texts.iter_mut() {
+        if visibility == Visibility::Hidden {
+            continue;
+        }
+        text.sections[0].value = format!(
+            "FPS: {:.0}\nFrame: {:.2} ms\nFixed ticks/frame: {:.2}\nEnemies: {}\nFood: {}\nRope points: {}\nMeshes: {}\nMaterials: {}",
+            value(&FrameTimeDiagnosticsPlugin::FPS),
+            value(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+            value(&FIXED_TICKS),
+            count(&ENEMY_COUNT),
+            count(&FOOD_COUNT),
+            count(&ROPE_POINT_COUNT),
+            count(&MESH_COUNT),
+            count(&MATERIAL_COUNT),
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_polyline::prelude::*;
+
+#[cfg(feature = "debug-overlay")]
+use crate::debug_overlay::DebugOverlayPlugin;
+use crate::game::{Enemy, Food};
+use crate::hud::HudPlugin;
+#[cfg(debug_assertions)]
+use crate::inspect::InspectorPlugin;
+use crate::obstacle::{ArenaWalls, Obstacle, ObstacleShape};
+#[cfg(feature = "perf-hud")]
+use crate::perf_hud::PerfHudPlugin;
+use crate::rope::Rope;
+#[cfg(feature = "tuning-panel")]
+use crate::tuning::TuningPanelPlugin;
+
+const CIRCLE_SEGMENTS: usize = 24;
+
+// Alternative to RenderPlugin that draws everything as bevy_polyline lines. Polylines
+// only render through a 3D camera, so an orthographic one looks down on the arena.
+pub struct PolylineRenderPlugin;
+
+impl Plugin for PolylineRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((PolylinePlugin, HudPlugin))
+            .insert_resource(Msaa::Sample4)
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    attach_rope_lines,
+                    update_rope_lines,
+                    attach_circle_lines,
+                    follow_enemies,
+                    attach_obstacle_lines,
+                    update_walls_line.run_if(resource_changed_or_removed::<ArenaWalls>()),
+                ),
+            );
+        #[cfg(feature = "debug-overlay")]
+        app.add_plugins(DebugOverlayPlugin);
+        #[cfg(feature = "perf-hud")]
+        app.add_plugins(PerfHudPlugin);
+        #[cfg(feature = "tuning-panel")]
+        app.add_plugins(TuningPanelPlugin);
+        #[cfg(debug_assertions)]
+        app.add_plugins(InspectorPlugin);
+    }
+}
+
+#[derive(Resource)]
+struct LineMaterials {
+    enemy: Handle<PolylineMaterial>,
+    food: Handle<PolylineMaterial>,
+    level: Handle<PolylineMaterial>,
+}
+
+#[derive(Component)]
+struct WallsLine;
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<PolylineMaterial>>) {
+    commands.spawn(Camera3dBundle {
+        projection: OrthographicProjection::default().into(),
+        transform: Transform::from_xyz(0.0, 0.0, 100.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+    let mut material = |color: Color| {
+        materials.add(PolylineMaterial {
+            width: 2.0,
+            color,
+            perspective: false,
+            ..default()
+        })
+    };
+    commands.insert_resource(LineMaterials {
+        enemy: material(Color::RED),
+        food: material(Color::BLUE),
+        level: material(Color::GRAY),
+    });
+}
+
+fn to_vertices(points: impl IntoIterator<Item = Vec2>) -> Vec<Vec3> {
+    points.into_iter().map(|point| point.extend(0.0)).collect()
+}
+
+// Closed loop around the origin
+fn circle(radius: f32) -> Vec<Vec3> {
+    to_vertices(
+        (0..=CIRCLE_SEGMENTS)
+            .map(|i| Vec2::from_angle(TAU * i as f32 / CIRCLE_SEGMENTS as f32) * radius),
+    )
+}
+
+fn closed(points: &[Vec2]) -> Vec<Vec3> {
+    to_vertices(points.iter().chain(points.first()).copied())
+}
+
+fn attach_rope_lines(
+    mut commands: Commands,
+    ropes: Query<(Entity, &Rope), Added<Rope>>,
+    mut polylines: ResMut<Assets<Polyline>>,
+    mut materials: ResMut<Assets<PolylineMaterial>>,
+) {
+    for (entity, rope) in ropes.iter() {
+        commands.entity(entity).insert(PolylineBundle {
+            polyline: polylines.add(Polyline {
+                vertices: to_vertices(rope.points.iter().copied()),
+            }),
+            material: materials.add(PolylineMaterial {
+                width: rope.thickness,
+                color: rope.color,
+                perspective: false,
+                ..default()
+            }),
+            ..default()
+        });
+    }
+}
+
+type RopeLine<'a> = (&'a Rope, &'a Handle<Polyline>, &'a Handle<PolylineMaterial>);
+
+fn update_rope_lines(
+    ropes: Query<RopeLine, Changed<Rope>>,
+    mut polylines: ResMut<Assets<Polyline>>,
+    mut materials: ResMut<Assets<PolylineMaterial>>,
+) {
+    for (rope, polyline, material) in ropes.iter() {
+        if let Some(polyline) = polylines.get_mut(polyline) {
+            polyline.vertices = to_vertices(rope.points.iter().copied());
+        }
+        // Only touch the material when the color changes, e.g. while a piece fades
+        if materials
+            .get(material)
+            .is_some_and(|line| line.color != rope.color)
+        {
+            if let Some(line) = materials.get_mut(material) {
+                line.color = rope.color;
+            }
+        }
+    }
+}
+
+fn attach_circle_lines(
+    mut commands: Commands,
+    enemies: Query<(Entity, &Enemy), Added<Enemy>>,
+    food: Query<(Entity, &Food), Added<Food>>,
+    mut polylines: ResMut<Assets<Polyline>>,
+    line_materials: Res<LineMaterials>,
+) {
+    let circles =
+        enemies
+            .iter()
+            .map(|(entity, enemy)| (entity, enemy.position, enemy.radius, &line_materials.enemy))
+            .chain(food.iter().map(|(entity, piece)| {
+                (entity, piece.position, piece.radius, &line_materials.food)
+            }));
+    for (entity, position, radius, material) in circles {
+        commands.entity(entity).insert(PolylineBundle {
+            polyline: polylines.add(Polyline {
+                vertices: circle(radius),
+            }),
+            material: material.clone(),
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..default()
+        });
+    }
+}
+
+fn follow_enemies(mut enemies: Query<(&Enemy, &mut Transform)>) {
+    for (enemy, mut transform) in enemies.iter_mut() {
+        transform.translation = enemy.position.extend(0.0);
+    }
+}
+
+fn attach_obstacle_lines(
+    mut commands: Commands,
+    obstacles: Query<(Entity, &Obstacle), Added<Obstacle>>,
+    mut polylines: ResMut<Assets<Polyline>>,
+    line_materials: Res<LineMaterials>,
+) {
+    for (entity, obstacle) in obstacles.iter() {
+        let vertices = match &obstacle.shape {
+            ObstacleShape::Circle { radius } => circle(*radius),
+            ObstacleShape::Box { half_extents } => closed(&[
+                *half_extents * Vec2::new(-1.0, -1.0),
+                *half_extents * Vec2::new(1.0, -1.0),
+                *half_extents,
+                *half_extents * Vec2::new(-1.0, 1.0),
+            ]),
+            ObstacleShape::Polygon { points } => closed(points),
+        };
+        commands.entity(entity).insert(PolylineBundle {
+            polyline: polylines.add(Polyline { vertices }),
+            material: line_materials.level.clone(),
+            transform: Transform::from_translation(obstacle.position.extend(0.0)),
+            ..default()
+        });
+    }
+}
+
+fn update_walls_line(
+    mut commands: Commands,
+    walls: Option<Res<ArenaWalls>>,
+    lines: Query<Entity, With<WallsLine>>,
+    mut polylines: ResMut<Assets<Polyline>>,
+    line_materials: Res<LineMaterials>,
+) {
+    for entity in lines.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(walls) = walls else {
+        return;
+    };
+    let bounds = walls.bounds;
+    commands.spawn((
+        PolylineBundle {
+            polyline: polylines.add(Polyline {
+                vertices: closed(&[
+                    bounds.min,
+                    Vec2::new(bounds.max.x, bounds.min.y),
+                    bounds.max,
+                    Vec2::new(bounds.min.x, bounds.max.y),
+                ]),
+            }),
+            material: line_materials.level.clone(),
+            ..default()
+        },
+        WallsLine,
+    ));
+}
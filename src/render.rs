@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "debug-overlay")]
+use crate::debug_overlay::DebugOverlayPlugin;
+use crate::hud::HudPlugin;
+#[cfg(debug_assertions)]
+use crate::inspect::InspectorPlugin;
+#[cfg(feature = "render-mesh")]
+use crate::mesh::MeshRenderPlugin;
+#[cfg(feature = "perf-hud")]
+use crate::perf_hud::PerfHudPlugin;
+#[cfg(feature = "render-lyon")]
+use crate::shapes::ShapesPlugin;
+#[cfg(feature = "tuning-panel")]
+use crate::tuning::TuningPanelPlugin;
+
+// Draws the simulation and the HUD; add alongside GamePlugin for the windowed game.
+// What gets drawn depends on the enabled `render-mesh` and `render-lyon` features.
+pub struct RenderPlugin;
+
+impl Plugin for RenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(HudPlugin)
+            .insert_resource(Msaa::Sample4)
+            .add_systems(Startup, setup);
+        #[cfg(feature = "render-mesh")]
+        app.add_plugins(MeshRenderPlugin);
+        #[cfg(feature = "render-lyon")]
+        app.add_plugins(ShapesPlugin);
+        #[cfg(feature = "debug-overlay")]
+        app.add_plugins(DebugOverlayPlugin);
+        #[cfg(feature = "perf-hud")]
+        app.add_plugins(PerfHudPlugin);
+        #[cfg(feature = "tuning-panel")]
+        app.add_plugins(TuningPanelPlugin);
+        #[cfg(debug_assertions)]
+        app.add_plugins(InspectorPlugin);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
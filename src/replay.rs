@@ -0,0 +1,338 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use thiserror::Error;
+
+use crate::arena::{ActiveArena, ApplyArenaSet};
+use crate::config::GameConfig;
+use crate::game::{Enemy, Food, Health, MousePosition, Score};
+use crate::rng::GameRng;
+use crate::rope::Rope;
+
+// Replay file layout, little endian:
+// magic "SVRP", version u8, seed u64, tick count u32, one (x, y) f32 pair per
+// fixed tick, then the u64 state hash taken after the last tick
+const MAGIC: &[u8; 4] = b"SVRP";
+const VERSION: u8 = 1;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("replay io failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("not a replay file")]
+    BadMagic,
+    #[error("unsupported replay version {0}")]
+    UnsupportedVersion(u8),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub ticks: Vec<Vec2>,
+    pub final_hash: u64,
+}
+
+impl Replay {
+    pub fn write(&self, writer: &mut impl Write) -> Result<(), ReplayError> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.seed.to_le_bytes())?;
+        writer.write_all(&(self.ticks.len() as u32).to_le_bytes())?;
+        for tick in self.ticks.iter() {
+            writer.write_all(&tick.x.to_le_bytes())?;
+            writer.write_all(&tick.y.to_le_bytes())?;
+        }
+        writer.write_all(&self.final_hash.to_le_bytes())?;
+        Ok(())
+    }
+
+    pub fn read(reader: &mut impl Read) -> Result<Self, ReplayError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let mut version = [0; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != VERSION {
+            return Err(ReplayError::UnsupportedVersion(version[0]));
+        }
+        let seed = u64::from_le_bytes(read_array(reader)?);
+        let count = u32::from_le_bytes(read_array(reader)?);
+        let ticks = (0..count)
+            .map(|_| {
+                let x = f32::from_le_bytes(read_array(reader)?);
+                let y = f32::from_le_bytes(read_array(reader)?);
+                Ok(Vec2::new(x, y))
+            })
+            .collect::<Result<Vec<_>, ReplayError>>()?;
+        let final_hash = u64::from_le_bytes(read_array(reader)?);
+        Ok(Replay {
+            seed,
+            ticks,
+            final_hash,
+        })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), ReplayError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Self, ReplayError> {
+        Replay::read(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], ReplayError> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+// Present while the cursor stream of the current run is being captured
+#[derive(Resource)]
+pub struct InputRecording {
+    path: String,
+    ticks: Vec<Vec2>,
+}
+
+// Present while a recorded run is fed back instead of the cursor
+#[derive(Resource)]
+pub struct InputPlayback {
+    replay: Replay,
+    next_tick: usize,
+    verified: bool,
+}
+
+// FNV-1a over every piece of simulation state that affects later ticks
+pub fn state_hash(
+    ropes: &Query<(&Rope, Option<&Score>, Option<&Health>)>,
+    enemies: &Query<&Enemy>,
+    food: &Query<&Food>,
+) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+    for (rope, score, health) in ropes.iter() {
+        for point in rope.points.iter().chain(rope.prev_points.iter()) {
+            feed(&point.x.to_le_bytes());
+            feed(&point.y.to_le_bytes());
+        }
+        feed(&score.map_or(0, |score| score.value).to_le_bytes());
+        feed(&health.map_or(0, |health| health.value).to_le_bytes());
+    }
+    for enemy in enemies.iter() {
+        for value in [
+            enemy.position.x,
+            enemy.position.y,
+            enemy.position_prev.x,
+            enemy.position_prev.y,
+            enemy.radius,
+        ] {
+            feed(&value.to_le_bytes());
+        }
+    }
+    for piece in food.iter() {
+        for value in [piece.position.x, piece.position.y, piece.radius] {
+            feed(&value.to_le_bytes());
+        }
+    }
+    hash
+}
+
+impl InputPlayback {
+    // Fixed ticks of input in the replay
+    pub fn ticks(&self) -> usize {
+        self.replay.ticks.len()
+    }
+}
+
+pub fn record_input(mouse_pos: Res<MousePosition>, mut recording: ResMut<InputRecording>) {
+    recording.ticks.push(mouse_pos.position);
+}
+
+pub fn play_input(mut mouse_pos: ResMut<MousePosition>, mut playback: ResMut<InputPlayback>) {
+    if let Some(position) = playback.replay.ticks.get(playback.next_tick).copied() {
+        mouse_pos.position = position;
+        playback.next_tick += 1;
+    }
+}
+
+// Compares the state after the last replayed tick with the hash stored in the file
+pub fn verify_replay(
+    mut playback: ResMut<InputPlayback>,
+    ropes: Query<(&Rope, Option<&Score>, Option<&Health>)>,
+    enemies: Query<&Enemy>,
+    food: Query<&Food>,
+) {
+    if playback.verified || playback.next_tick < playback.replay.ticks.len() {
+        return;
+    }
+    playback.verified = true;
+    let hash = state_hash(&ropes, &enemies, &food);
+    if hash == playback.replay.final_hash {
+        info!(
+            "replay verified after {} ticks (hash {hash:016x})",
+            playback.next_tick
+        );
+    } else {
+        error!(
+            "replay diverged after {} ticks: expected hash {:016x}, got {hash:016x}",
+            playback.next_tick, playback.replay.final_hash
+        );
+    }
+}
+
+// A restarted run begins a fresh recording or rewinds the replay
+fn restart_replay(
+    mut recording: Option<ResMut<InputRecording>>,
+    mut playback: Option<ResMut<InputPlayback>>,
+) {
+    if let Some(recording) = recording.as_mut() {
+        recording.ticks.clear();
+    }
+    if let Some(playback) = playback.as_mut() {
+        playback.next_tick = 0;
+        playback.verified = false;
+    }
+}
+
+fn save_recording(
+    mut exit: EventReader<AppExit>,
+    recording: Res<InputRecording>,
+    rng: Res<GameRng>,
+    ropes: Query<(&Rope, Option<&Score>, Option<&Health>)>,
+    enemies: Query<&Enemy>,
+    food: Query<&Food>,
+) {
+    if exit.read().last().is_none() {
+        return;
+    }
+    let replay = Replay {
+        seed: rng.seed,
+        ticks: recording.ticks.clone(),
+        final_hash: state_hash(&ropes, &enemies, &food),
+    };
+    match replay.save(&recording.path) {
+        Ok(()) => info!(
+            "saved {} ticks of input to {}",
+            replay.ticks.len(),
+            recording.path
+        ),
+        Err(err) => error!("could not save replay to {}: {err}", recording.path),
+    }
+}
+
+// Sets up recording or playback from GameConfig; add after ConfigPlugin and before
+// GameRng is initialised so a replay can supply its seed
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let config = app.world.resource::<GameConfig>().clone();
+        if let Some(path) = config.replay {
+            match Replay::load(&path) {
+                Ok(replay) => {
+                    info!("replaying {} ticks from {path}", replay.ticks.len());
+                    app.insert_resource(GameRng::new(replay.seed));
+                    app.insert_resource(InputPlayback {
+                        replay,
+                        next_tick: 0,
+                        verified: false,
+                    });
+                }
+                Err(err) => error!("could not load replay {path}: {err}"),
+            }
+        } else if let Some(path) = config.record {
+            app.insert_resource(InputRecording {
+                path,
+                ticks: Vec::new(),
+            });
+            app.add_systems(Last, save_recording);
+        }
+        app.add_systems(
+            Update,
+            restart_replay
+                .after(ApplyArenaSet)
+                .run_if(resource_changed::<ActiveArena>),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        Replay {
+            seed: 0x0123_4567_89ab_cdef,
+            ticks: vec![Vec2::new(1.5, -2.0), Vec2::ZERO, Vec2::new(-300.25, 120.0)],
+            final_hash: 0xfeed_beef,
+        }
+    }
+
+    fn encode(replay: &Replay) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        replay.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn written_replay_reads_back() {
+        let replay = replay();
+        let bytes = encode(&replay);
+        assert_eq!(bytes.len(), 4 + 1 + 8 + 4 + 3 * 8 + 8);
+        assert_eq!(Replay::read(&mut bytes.as_slice()).unwrap(), replay);
+
+        let empty = Replay {
+            ticks: Vec::new(),
+            ..replay
+        };
+        assert_eq!(Replay::read(&mut encode(&empty).as_slice()).unwrap(), empty);
+    }
+
+    #[test]
+    fn truncated_replay_is_an_error() {
+        let bytes = encode(&replay());
+        for len in 0..bytes.len() {
+            assert!(matches!(
+                Replay::read(&mut &bytes[..len]),
+                Err(ReplayError::Io(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn corrupt_header_is_an_error() {
+        let mut bytes = encode(&replay());
+        bytes[0] = b'X';
+        assert!(matches!(
+            Replay::read(&mut bytes.as_slice()),
+            Err(ReplayError::BadMagic)
+        ));
+
+        let mut bytes = encode(&replay());
+        bytes[4] = VERSION + 1;
+        assert!(matches!(
+            Replay::read(&mut bytes.as_slice()),
+            Err(ReplayError::UnsupportedVersion(v)) if v == VERSION + 1
+        ));
+
+        // A tick count far beyond the data fails on the missing ticks
+        let mut bytes = encode(&replay());
+        bytes[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            Replay::read(&mut bytes.as_slice()),
+            Err(ReplayError::Io(_))
+        ));
+    }
+}
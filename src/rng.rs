@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::config::GameConfig;
+
+// Source of every random number in the simulation, so a seed replays a run exactly
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    // Rewinds the sequence to the start of the seed
+    pub fn reseed(&mut self) {
+        self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+    }
+}
+
+// Position in the seed's random sequence, so a restored run draws the same numbers
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct RngState {
+    pub seed: u64,
+    // RON has no u128; no run gets near 2^64 words
+    pub word_pos: u64,
+}
+
+impl FromWorld for GameRng {
+    fn from_world(world: &mut World) -> Self {
+        let seed = world
+            .resource::<GameConfig>()
+            .seed
+            .unwrap_or_else(|| rand::thread_rng().gen());
+        info!("run seed: {seed}");
+        GameRng::new(seed)
+    }
+}
@@ -0,0 +1,680 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::config::GameConfig;
+
+// Samples per turn when tracing a coiled rope before spacing its points evenly
+const COIL_SAMPLES_PER_TURN: usize = 64;
+// Far more than any rope needs; bad config or arena data is rejected before allocating
+const MAX_POINTS: usize = 4096;
+const MAX_COIL_TURNS: f32 = (MAX_POINTS / COIL_SAMPLES_PER_TURN) as f32;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Rope {
+    pub points: Vec<Vec2>,
+    pub prev_points: Vec<Vec2>,
+    pub segment_length: f32,
+    pub thickness: f32,
+    pub elasticity: f32,
+    pub color: Color,
+    // A segment stretched past this multiple of segment_length tears the rope
+    pub tear_strain: f32,
+    // Whether point 0 follows the cursor; detached pieces simulate freely
+    pub pinned: bool,
+    // Weight of each point when pushed against another rope
+    pub mass: f32,
+}
+
+impl Rope {
+    // Returns the index of a segment that was stretched past its breaking point
+    pub fn update(&mut self, mouse_pos: Vec2, config: &GameConfig) -> Option<usize> {
+        self.update_rope(mouse_pos, config)
+    }
+
+    fn update_rope(&mut self, mouse_pos: Vec2, config: &GameConfig) -> Option<usize> {
+        let first = if self.pinned { 1 } else { 0 };
+        for i in first..self.points.len() {
+            let current = self.points[i];
+            let prev = self.prev_points[i];
+            let velocity = current - prev;
+            let next_position = current + velocity / config.rope_damping; // Apply gravity here if needed
+            self.prev_points[i] = self.points[i];
+            self.points[i] = next_position;
+        }
+
+        self.constrain_points(config.constraint_iterations * config.substeps.max(1));
+
+        // Measure strain before the head jumps to the cursor, so only stretch the
+        // solver could not resolve counts towards tearing
+        let torn = self.overstretched_segment();
+
+        if self.pinned {
+            self.points[0] = mouse_pos;
+        }
+        torn
+    }
+
+    // Distance the midpoint of the last segment moved over the previous tick
+    pub fn tail_speed(&self) -> f32 {
+        let n = self.points.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let now = (self.points[n - 1] + self.points[n - 2]) / 2.0;
+        let before = (self.prev_points[n - 1] + self.prev_points[n - 2]) / 2.0;
+        now.distance(before)
+    }
+
+    fn overstretched_segment(&self) -> Option<usize> {
+        let max_length = self.segment_length * self.tear_strain;
+        (0..self.points.len().saturating_sub(1))
+            .find(|&i| self.points[i].distance(self.points[i + 1]) > max_length)
+    }
+
+    // Cuts the rope after `segment`, returning the detached tail as a free rope. The cut
+    // moves inwards so both pieces keep a segment; a rope too short for that stays whole.
+    pub fn split_off(&mut self, segment: usize) -> Option<Rope> {
+        let count = self.points.len();
+        if count < 4 {
+            return None;
+        }
+        let at = (segment + 1).clamp(2, count - 2);
+        let points = self.points.split_off(at);
+        let prev_points = self.prev_points.split_off(at);
+        Some(Rope {
+            points,
+            prev_points,
+            segment_length: self.segment_length,
+            thickness: self.thickness,
+            elasticity: self.elasticity,
+            color: self.color,
+            tear_strain: f32::INFINITY,
+            pinned: false,
+            mass: self.mass,
+        })
+    }
+
+    fn constrain_points(&mut self, iterations: usize) {
+        let count = self.points.len();
+        for _ in 0..iterations {
+            for i in 0..(count - 1) {
+                let point_a = self.points[i];
+                let point_b = self.points[i + 1];
+                let delta = point_b - point_a;
+                let distance = delta.length();
+                let difference = self.segment_length - distance;
+                // Coincident points have no direction, so pull them apart along an arbitrary axis
+                let direction = delta.try_normalize().unwrap_or(Vec2::Y);
+                let correction = direction * (difference / self.elasticity);
+                if i != 0 || !self.pinned {
+                    self.points[i] -= correction;
+                }
+                self.points[i + 1] += correction;
+            }
+        }
+    }
+}
+
+// How a rope is drawn; ropes without one get the default look
+#[derive(Component, Clone, Debug, Deserialize, Reflect)]
+#[reflect(Component)]
+#[serde(default)]
+pub struct RopeStyle {
+    // Blended from head to tail and multiplied with Rope::color
+    pub head_color: Color,
+    pub tail_color: Color,
+    // Width at the tail as a share of the width at the head
+    pub taper: f32,
+    pub head: HeadShape,
+    // Color the rope briefly turns when it takes damage
+    pub flash_color: Color,
+    pub flash_seconds: f32,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Reflect)]
+pub enum HeadShape {
+    // Only the rounded end of the stroke
+    Round,
+    // Disc `scale` times the rope width across
+    Disc { scale: f32 },
+    // Diamond pointing away from the rope, `scale` times the rope width long
+    Diamond { scale: f32 },
+}
+
+impl Default for RopeStyle {
+    fn default() -> Self {
+        RopeStyle {
+            head_color: Color::WHITE,
+            tail_color: Color::WHITE,
+            taper: 1.0,
+            head: HeadShape::Disc { scale: 2.0 },
+            flash_color: Color::RED,
+            flash_seconds: 0.25,
+        }
+    }
+}
+
+impl RopeStyle {
+    // Look of a piece torn off this rope, which has no head of its own
+    pub fn detached(&self) -> RopeStyle {
+        RopeStyle {
+            head: HeadShape::Round,
+            ..self.clone()
+        }
+    }
+}
+
+// Initial layout of a rope, traced from the head to the tail
+#[derive(Clone, Debug)]
+pub enum RopeShape {
+    Straight {
+        start: Vec2,
+        end: Vec2,
+    },
+    // Spiral winding inwards from `radius` to `center`
+    Coiled {
+        center: Vec2,
+        radius: f32,
+        turns: f32,
+    },
+    // Polyline the points are spread evenly along
+    Custom(Vec<Vec2>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Resolution {
+    Points(usize),
+    SegmentLength(f32),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RopeError {
+    #[error("a rope needs at least 2 points, got {0}")]
+    TooFewPoints(usize),
+    #[error("segment length must be positive, got {0}")]
+    InvalidSegmentLength(f32),
+    #[error("rope shape has no length")]
+    ZeroLength,
+    #[error("rope shape contains a non-finite point")]
+    NonFinitePoint,
+    #[error("thickness must be positive, got {0}")]
+    InvalidThickness(f32),
+    #[error("stiffness must be in (0, 1], got {0}")]
+    InvalidStiffness(f32),
+    #[error("elasticity must be at least 2, got {0}")]
+    InvalidElasticity(f32),
+    #[error("mass must be positive, got {0}")]
+    InvalidMass(f32),
+    #[error("a rope has at most {MAX_POINTS} points, got {0}")]
+    TooManyPoints(f32),
+    #[error("a coil has at most {MAX_COIL_TURNS} turns, got {0}")]
+    TooManyTurns(f32),
+}
+
+// Builds a pinned rope; anything not set falls back to the GameConfig values, a
+// white color, unit mass and as many points as the traced shape has, spaced evenly
+// along it, so corners of unevenly split shapes are not kept
+#[derive(Clone, Debug)]
+pub struct RopeBuilder {
+    shape: RopeShape,
+    resolution: Option<Resolution>,
+    thickness: Option<f32>,
+    stiffness: Option<f32>,
+    color: Color,
+    mass: f32,
+}
+
+impl RopeBuilder {
+    pub fn new(shape: RopeShape) -> Self {
+        RopeBuilder {
+            shape,
+            resolution: None,
+            thickness: None,
+            stiffness: None,
+            color: Color::WHITE,
+            mass: 1.0,
+        }
+    }
+
+    pub fn straight(start: Vec2, end: Vec2) -> Self {
+        RopeBuilder::new(RopeShape::Straight { start, end })
+    }
+
+    pub fn points(mut self, count: usize) -> Self {
+        self.resolution = Some(Resolution::Points(count));
+        self
+    }
+
+    // Picks the point count whose segments come closest to `length`
+    pub fn segment_length(mut self, length: f32) -> Self {
+        self.resolution = Some(Resolution::SegmentLength(length));
+        self
+    }
+
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = Some(thickness);
+        self
+    }
+
+    // Share of each segment's stretch undone per solver pass, up to 1 for a rope
+    // that corrects itself fully
+    pub fn stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = Some(stiffness);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn build(self, config: &GameConfig) -> Result<Rope, RopeError> {
+        let thickness = self.thickness.unwrap_or(config.rope_thickness);
+        if !positive(thickness) {
+            return Err(RopeError::InvalidThickness(thickness));
+        }
+        // A free segment is fully corrected when each end moves half the error
+        let elasticity = match self.stiffness {
+            Some(stiffness) if positive(stiffness) && stiffness <= 1.0 => 2.0 / stiffness,
+            Some(stiffness) => return Err(RopeError::InvalidStiffness(stiffness)),
+            None if config.rope_elasticity >= 2.0 && config.rope_elasticity.is_finite() => {
+                config.rope_elasticity
+            }
+            None => return Err(RopeError::InvalidElasticity(config.rope_elasticity)),
+        };
+        if !positive(self.mass) {
+            return Err(RopeError::InvalidMass(self.mass));
+        }
+
+        if let RopeShape::Coiled { turns, .. } = self.shape {
+            if turns > MAX_COIL_TURNS {
+                return Err(RopeError::TooManyTurns(turns));
+            }
+        }
+        let path = self.shape.trace();
+        if path.iter().any(|point| !point.is_finite()) {
+            return Err(RopeError::NonFinitePoint);
+        }
+        let length: f32 = path.windows(2).map(|pair| pair[0].distance(pair[1])).sum();
+        if !positive(length) {
+            return Err(RopeError::ZeroLength);
+        }
+        // Counted in f32 so a tiny segment length cannot overflow before the check
+        let count = match self.resolution {
+            Some(Resolution::Points(count)) => count as f32,
+            Some(Resolution::SegmentLength(segment)) if positive(segment) => {
+                (length / segment).round().max(1.0) + 1.0
+            }
+            Some(Resolution::SegmentLength(segment)) => {
+                return Err(RopeError::InvalidSegmentLength(segment))
+            }
+            None => path.len() as f32,
+        };
+        if count > MAX_POINTS as f32 {
+            return Err(RopeError::TooManyPoints(count));
+        }
+        let count = count as usize;
+        if count < 2 {
+            return Err(RopeError::TooFewPoints(count));
+        }
+
+        let points = resample(&path, length, count);
+        Ok(Rope {
+            prev_points: points.clone(),
+            points,
+            segment_length: length / (count - 1) as f32,
+            thickness,
+            elasticity,
+            color: self.color,
+            tear_strain: config.rope_tear_strain,
+            pinned: true,
+            mass: self.mass,
+        })
+    }
+}
+
+impl RopeShape {
+    fn trace(&self) -> Vec<Vec2> {
+        match self {
+            RopeShape::Straight { start, end } => vec![*start, *end],
+            RopeShape::Coiled {
+                center,
+                radius,
+                turns,
+            } => {
+                let samples = ((turns * COIL_SAMPLES_PER_TURN as f32).ceil() as usize).max(1);
+                (0..=samples)
+                    .map(|i| {
+                        let t = i as f32 / samples as f32;
+                        let angle = t * turns * std::f32::consts::TAU;
+                        *center + Vec2::from_angle(angle) * *radius * (1.0 - t)
+                    })
+                    .collect()
+            }
+            RopeShape::Custom(points) => points.clone(),
+        }
+    }
+}
+
+// Spreads `count` points evenly by distance along a polyline of total `length`
+fn resample(path: &[Vec2], length: f32, count: usize) -> Vec<Vec2> {
+    let mut points = Vec::with_capacity(count);
+    let mut segment = 0;
+    let mut walked = 0.0;
+    for i in 0..count {
+        let target = length * i as f32 / (count - 1) as f32;
+        while segment < path.len() - 2
+            && walked + path[segment].distance(path[segment + 1]) < target
+        {
+            walked += path[segment].distance(path[segment + 1]);
+            segment += 1;
+        }
+        let (a, b) = (path[segment], path[segment + 1]);
+        let span = a.distance(b);
+        let t = if span > 0.0 {
+            ((target - walked) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        points.push(a.lerp(b, t));
+    }
+    points
+}
+
+fn positive(value: f32) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn straight_rope(count: usize) -> Rope {
+        RopeBuilder::straight(Vec2::ZERO, Vec2::new(10.0 * (count as f32 - 1.0), 0.0))
+            .points(count)
+            .build(&GameConfig::default())
+            .unwrap()
+    }
+
+    fn max_segment_error(rope: &Rope) -> f32 {
+        rope.points
+            .windows(2)
+            .map(|pair| (pair[0].distance(pair[1]) - rope.segment_length).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn straight_rope_spaces_points_evenly() {
+        let rope = straight_rope(5);
+        assert_eq!(rope.points.len(), 5);
+        assert_eq!(rope.prev_points, rope.points);
+        assert_eq!(rope.segment_length, 10.0);
+        for (i, point) in rope.points.iter().enumerate() {
+            assert!(point.distance(Vec2::new(10.0 * i as f32, 0.0)) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn tail_speed_follows_the_last_segment() {
+        let mut rope = straight_rope(4);
+        assert_eq!(rope.tail_speed(), 0.0);
+        rope.points[3] += Vec2::new(0.0, 4.0);
+        rope.points[2] += Vec2::new(0.0, 2.0);
+        assert_eq!(rope.tail_speed(), 3.0);
+        // The head moving alone does not count
+        rope.points[0] += Vec2::new(50.0, 0.0);
+        assert_eq!(rope.tail_speed(), 3.0);
+    }
+
+    #[test]
+    fn builder_takes_unset_tuning_from_config() {
+        let config = GameConfig {
+            rope_thickness: 9.0,
+            rope_elasticity: 3.0,
+            ..default()
+        };
+        let rope = RopeBuilder::straight(Vec2::ZERO, Vec2::new(0.0, 30.0))
+            .points(4)
+            .build(&config)
+            .unwrap();
+        assert_eq!(rope.thickness, 9.0);
+        assert_eq!(rope.elasticity, 3.0);
+        assert_eq!(rope.mass, 1.0);
+        assert!(rope.pinned);
+        assert!(rope.points[3].distance(Vec2::new(0.0, 30.0)) < 1e-4);
+    }
+
+    #[test]
+    fn builder_applies_overrides() {
+        let rope = RopeBuilder::straight(Vec2::ZERO, Vec2::new(40.0, 0.0))
+            .points(3)
+            .thickness(4.0)
+            .stiffness(0.5)
+            .color(Color::RED)
+            .mass(2.0)
+            .build(&GameConfig::default())
+            .unwrap();
+        assert_eq!(rope.thickness, 4.0);
+        assert_eq!(rope.elasticity, 4.0);
+        assert_eq!(rope.color, Color::RED);
+        assert_eq!(rope.mass, 2.0);
+    }
+
+    #[test]
+    fn segment_length_picks_the_closest_point_count() {
+        let rope = RopeBuilder::straight(Vec2::ZERO, Vec2::new(100.0, 0.0))
+            .segment_length(12.0)
+            .build(&GameConfig::default())
+            .unwrap();
+        assert_eq!(rope.points.len(), 9);
+        assert_eq!(rope.segment_length, 12.5);
+    }
+
+    #[test]
+    fn coiled_rope_has_even_segments_and_starts_outside() {
+        let rope = RopeBuilder::new(RopeShape::Coiled {
+            center: Vec2::new(10.0, 10.0),
+            radius: 50.0,
+            turns: 2.0,
+        })
+        .points(40)
+        .build(&GameConfig::default())
+        .unwrap();
+        assert_eq!(rope.points.len(), 40);
+        assert!(rope.points[0].distance(Vec2::new(60.0, 10.0)) < 1e-3);
+        assert!(rope.points[39].distance(Vec2::new(10.0, 10.0)) < 1e-3);
+        // Chords are never longer than the arc they span, so no segment starts stretched
+        assert!(rope
+            .points
+            .windows(2)
+            .all(|pair| pair[0].distance(pair[1]) <= rope.segment_length + 1e-3));
+    }
+
+    #[test]
+    fn oversized_point_counts_are_rejected() {
+        let config = GameConfig::default();
+        let straight = || RopeBuilder::straight(Vec2::ZERO, Vec2::new(100.0, 0.0));
+        assert_eq!(
+            straight().segment_length(1e-6).build(&config).err(),
+            Some(RopeError::TooManyPoints(1e8 + 1.0))
+        );
+        assert!(matches!(
+            straight().points(usize::MAX).build(&config),
+            Err(RopeError::TooManyPoints(_))
+        ));
+        assert_eq!(
+            straight()
+                .points(MAX_POINTS)
+                .build(&config)
+                .unwrap()
+                .points
+                .len(),
+            MAX_POINTS
+        );
+
+        let coil = |turns| RopeShape::Coiled {
+            center: Vec2::ZERO,
+            radius: 50.0,
+            turns,
+        };
+        assert_eq!(
+            RopeBuilder::new(coil(1e9)).points(10).build(&config).err(),
+            Some(RopeError::TooManyTurns(1e9))
+        );
+        assert!(RopeBuilder::new(coil(MAX_COIL_TURNS))
+            .points(10)
+            .build(&config)
+            .is_ok());
+    }
+
+    #[test]
+    fn custom_rope_spreads_one_point_per_corner_evenly_by_default() {
+        let corners = vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)];
+        let rope = RopeBuilder::new(RopeShape::Custom(corners.clone()))
+            .build(&GameConfig::default())
+            .unwrap();
+        assert_eq!(rope.points, corners);
+        assert_eq!(rope.segment_length, 10.0);
+
+        // With uneven segments the middle point lands halfway along, not on the corner
+        let corners = vec![Vec2::ZERO, Vec2::new(30.0, 0.0), Vec2::new(30.0, 10.0)];
+        let rope = RopeBuilder::new(RopeShape::Custom(corners))
+            .build(&GameConfig::default())
+            .unwrap();
+        assert_eq!(rope.points.len(), 3);
+        assert_eq!(rope.segment_length, 20.0);
+        assert!(rope.points[1].distance(Vec2::new(20.0, 0.0)) < 1e-4);
+        assert!(rope.points[2].distance(Vec2::new(30.0, 10.0)) < 1e-4);
+    }
+
+    #[test]
+    fn builder_rejects_invalid_ropes() {
+        let config = GameConfig::default();
+        let straight = || RopeBuilder::straight(Vec2::ZERO, Vec2::new(50.0, 0.0));
+        let error = |builder: RopeBuilder| builder.build(&config).err();
+
+        assert_eq!(
+            error(straight().points(1)),
+            Some(RopeError::TooFewPoints(1))
+        );
+        assert_eq!(
+            error(straight().segment_length(0.0)),
+            Some(RopeError::InvalidSegmentLength(0.0))
+        );
+        assert_eq!(
+            error(RopeBuilder::straight(Vec2::ONE, Vec2::ONE).points(4)),
+            Some(RopeError::ZeroLength)
+        );
+        assert_eq!(
+            error(RopeBuilder::straight(Vec2::ZERO, Vec2::NAN).points(4)),
+            Some(RopeError::NonFinitePoint)
+        );
+        assert_eq!(
+            error(straight().thickness(-1.0)),
+            Some(RopeError::InvalidThickness(-1.0))
+        );
+        assert_eq!(
+            error(straight().stiffness(1.5)),
+            Some(RopeError::InvalidStiffness(1.5))
+        );
+        let soft = GameConfig {
+            rope_elasticity: 1.0,
+            ..default()
+        };
+        assert_eq!(
+            straight().build(&soft).err(),
+            Some(RopeError::InvalidElasticity(1.0))
+        );
+        // A given stiffness replaces the config elasticity
+        assert!(straight().stiffness(1.0).build(&soft).is_ok());
+        assert_eq!(
+            error(straight().mass(0.0)),
+            Some(RopeError::InvalidMass(0.0))
+        );
+        assert_eq!(
+            error(RopeBuilder::new(RopeShape::Custom(vec![Vec2::ZERO]))),
+            Some(RopeError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn constrain_points_converges_to_segment_length() {
+        let mut rope = straight_rope(5);
+        rope.points[2] += Vec2::new(3.0, 8.0);
+        rope.points[4] = Vec2::new(80.0, -25.0);
+        let initial = max_segment_error(&rope);
+
+        rope.constrain_points(1);
+        let after_one = max_segment_error(&rope);
+        rope.constrain_points(1000);
+        let settled = max_segment_error(&rope);
+
+        assert!(after_one < initial);
+        assert!(settled < 0.01, "segments still off by {settled}");
+    }
+
+    #[test]
+    fn constrain_points_leaves_pinned_head_in_place() {
+        let mut rope = straight_rope(4);
+        rope.points[3] = Vec2::new(100.0, 0.0);
+        rope.constrain_points(50);
+        assert_eq!(rope.points[0], Vec2::ZERO);
+
+        rope.pinned = false;
+        rope.points[3] = Vec2::new(100.0, 0.0);
+        rope.constrain_points(50);
+        assert_ne!(rope.points[0], Vec2::ZERO);
+    }
+
+    #[test]
+    fn constrain_points_separates_coincident_points() {
+        let mut rope = straight_rope(3);
+        rope.points[1] = rope.points[0];
+        rope.points[2] = rope.points[0];
+        rope.constrain_points(1000);
+        assert!(rope.points.iter().all(|point| point.is_finite()));
+        assert!(max_segment_error(&rope) < 0.01);
+    }
+
+    #[test]
+    fn rope_update_pins_head_to_cursor() {
+        let mut rope = straight_rope(5);
+        let torn = rope.update(Vec2::new(5.0, 5.0), &GameConfig::default());
+        assert_eq!(torn, None);
+        assert_eq!(rope.points[0], Vec2::new(5.0, 5.0));
+    }
+
+    #[test]
+    fn split_off_detaches_a_free_tail() {
+        let mut rope = straight_rope(5);
+        let tail = rope.split_off(1).unwrap();
+        assert_eq!(rope.points.len(), 2);
+        assert_eq!(tail.points.len(), 3);
+        assert_eq!(tail.points[0], Vec2::new(20.0, 0.0));
+        assert!(!tail.pinned);
+    }
+
+    #[test]
+    fn split_off_at_either_end_keeps_two_points_per_piece() {
+        let mut rope = straight_rope(5);
+        let tail = rope.split_off(0).unwrap();
+        assert_eq!((rope.points.len(), tail.points.len()), (2, 3));
+
+        let mut rope = straight_rope(5);
+        let tail = rope.split_off(3).unwrap();
+        assert_eq!((rope.points.len(), tail.points.len()), (3, 2));
+        assert_eq!(rope.prev_points.len(), 3);
+        assert_eq!(tail.prev_points.len(), 2);
+
+        let mut rope = straight_rope(3);
+        assert!(rope.split_off(0).is_none());
+        assert_eq!(rope.points.len(), 3);
+    }
+}
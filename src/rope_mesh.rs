@@ -0,0 +1,415 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::NoFrustumCulling;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::game::RopeDamaged;
+use crate::rope::{HeadShape, Rope, RopeStyle};
+
+// Curve samples between two rope points and triangles in each rounded end
+const SUBDIVISIONS: usize = 8;
+const CAP_SEGMENTS: usize = 8;
+// Width of a diamond head relative to its length
+const DIAMOND_ASPECT: f32 = 0.6;
+
+// Draws every rope as a single smoothed stroke with rounded ends. Each rope owns
+// one mesh that is rewritten in place whenever the rope moves.
+pub struct RopeMeshPlugin;
+
+impl Plugin for RopeMeshPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_damage_flash,
+                fade_damage_flash,
+                attach_rope_meshes,
+                update_rope_meshes,
+            )
+                .chain(),
+        );
+    }
+}
+
+// A point on the stroke's center line; everything is interpolated between points
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrokePoint {
+    pub position: Vec2,
+    pub width: f32,
+    // Linear RGBA
+    pub color: Vec4,
+}
+
+impl StrokePoint {
+    fn lerp(self, other: StrokePoint, t: f32) -> StrokePoint {
+        StrokePoint {
+            position: self.position.lerp(other.position, t),
+            width: self.width + (other.width - self.width) * t,
+            color: self.color.lerp(other.color, t),
+        }
+    }
+}
+
+// Counts down while a damaged rope is drawn in its flash color
+#[derive(Component)]
+pub struct RopeFlash(pub Timer);
+
+fn linear(color: Color) -> Vec4 {
+    Vec4::from(color.as_linear_rgba_f32())
+}
+
+// Applies the style's gradient and taper to every rope point; `flash` blends the
+// whole rope towards the flash color
+pub fn stroke_points(rope: &Rope, style: &RopeStyle, flash: f32) -> Vec<StrokePoint> {
+    let tint = linear(rope.color);
+    let (head, tail, flash_color) = (
+        linear(style.head_color),
+        linear(style.tail_color),
+        linear(style.flash_color),
+    );
+    let last = rope.points.len().saturating_sub(1).max(1) as f32;
+    rope.points
+        .iter()
+        .enumerate()
+        .map(|(i, position)| {
+            let t = i as f32 / last;
+            StrokePoint {
+                position: *position,
+                width: rope.thickness * (1.0 + (style.taper - 1.0) * t),
+                color: head.lerp(tail, t).lerp(flash_color, flash) * tint,
+            }
+        })
+        .collect()
+}
+
+// Catmull-Rom spline through every point, with the ends repeated as their own
+// neighbours. Widths and colors follow the curve linearly.
+pub fn smooth(points: &[StrokePoint], subdivisions: usize) -> Vec<StrokePoint> {
+    if points.len() < 2 {
+        return points.to_vec();
+    }
+    let mut samples = Vec::with_capacity((points.len() - 1) * subdivisions + 1);
+    for i in 0..points.len() - 1 {
+        let p0 = points[i.saturating_sub(1)].position;
+        let p1 = points[i].position;
+        let p2 = points[i + 1].position;
+        let p3 = points[(i + 2).min(points.len() - 1)].position;
+        for step in 0..subdivisions {
+            let t = step as f32 / subdivisions as f32;
+            let t2 = t * t;
+            let t3 = t2 * t;
+            let position = 0.5
+                * (2.0 * p1
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3);
+            samples.push(StrokePoint {
+                position,
+                ..points[i].lerp(points[i + 1], t)
+            });
+        }
+    }
+    samples.push(points[points.len() - 1]);
+    samples
+}
+
+#[derive(Default)]
+pub struct StrokeGeometry {
+    pub positions: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+}
+
+impl StrokeGeometry {
+    fn push_vertex(&mut self, position: Vec2, color: Vec4) -> u32 {
+        self.positions.push([position.x, position.y, 0.0]);
+        self.colors.push(color.to_array());
+        self.positions.len() as u32 - 1
+    }
+
+    // Half disc around `point` bulging towards `outward`
+    fn push_cap(&mut self, point: StrokePoint, outward: Vec2) {
+        let center = self.push_vertex(point.position, point.color);
+        let start = -outward.perp();
+        for k in 0..=CAP_SEGMENTS {
+            let direction = Vec2::from_angle(PI * k as f32 / CAP_SEGMENTS as f32).rotate(start);
+            let vertex =
+                self.push_vertex(point.position + direction * point.width / 2.0, point.color);
+            if k > 0 {
+                self.indices.extend([center, vertex - 1, vertex]);
+            }
+        }
+    }
+
+    // Marks the rope head at `point`, with `forward` pointing away from the rope
+    pub fn push_head(&mut self, shape: HeadShape, point: StrokePoint, forward: Vec2) {
+        match shape {
+            HeadShape::Round => {}
+            HeadShape::Disc { scale } => {
+                let radius = point.width * scale / 2.0;
+                let center = self.push_vertex(point.position, point.color);
+                let segments = 2 * CAP_SEGMENTS;
+                for k in 0..=segments {
+                    let direction = Vec2::from_angle(2.0 * PI * k as f32 / segments as f32);
+                    let vertex = self.push_vertex(point.position + direction * radius, point.color);
+                    if k > 0 {
+                        self.indices.extend([center, vertex - 1, vertex]);
+                    }
+                }
+            }
+            HeadShape::Diamond { scale } => {
+                let along = forward * point.width * scale / 2.0;
+                let across = forward.perp() * point.width * scale * DIAMOND_ASPECT / 2.0;
+                let tip = self.push_vertex(point.position + along, point.color);
+                self.push_vertex(point.position + across, point.color);
+                self.push_vertex(point.position - along, point.color);
+                self.push_vertex(point.position - across, point.color);
+                self.indices
+                    .extend([tip, tip + 1, tip + 2, tip, tip + 2, tip + 3]);
+            }
+        }
+    }
+}
+
+// Triangulates a thick line through `samples` with a rounded cap at each end
+pub fn tessellate(samples: &[StrokePoint]) -> StrokeGeometry {
+    let mut geometry = StrokeGeometry::default();
+    // Coincident samples have no direction to offset the edges from
+    let mut kept: Vec<StrokePoint> = Vec::with_capacity(samples.len());
+    for sample in samples {
+        if kept
+            .last()
+            .is_none_or(|last| last.position.distance(sample.position) > 1e-4)
+        {
+            kept.push(*sample);
+        }
+    }
+    let Some(first) = kept.first().copied() else {
+        return geometry;
+    };
+
+    let count = kept.len();
+    let mut tangent = Vec2::X;
+    let mut tangents = Vec::with_capacity(count);
+    for i in 0..count {
+        let ahead = kept[(i + 1).min(count - 1)].position;
+        let behind = kept[i.saturating_sub(1)].position;
+        tangent = (ahead - behind).try_normalize().unwrap_or(tangent);
+        tangents.push(tangent);
+    }
+
+    for (sample, tangent) in kept.iter().zip(tangents.iter()) {
+        let offset = tangent.perp() * sample.width / 2.0;
+        geometry.push_vertex(sample.position + offset, sample.color);
+        geometry.push_vertex(sample.position - offset, sample.color);
+    }
+    for i in 0..count as u32 - 1 {
+        let (left, right) = (2 * i, 2 * i + 1);
+        geometry
+            .indices
+            .extend([left, right, left + 2, right, right + 2, left + 2]);
+    }
+
+    geometry.push_cap(first, -tangents[0]);
+    geometry.push_cap(kept[count - 1], tangents[count - 1]);
+    geometry
+}
+
+fn write_mesh(mesh: &mut Mesh, rope: &Rope, style: &RopeStyle, flash: f32) {
+    let points = stroke_points(rope, style, flash);
+    let mut geometry = tessellate(&smooth(&points, SUBDIVISIONS));
+    if let [head, next, ..] = points.as_slice() {
+        let forward = (head.position - next.position)
+            .try_normalize()
+            .unwrap_or(Vec2::X);
+        geometry.push_head(style.head, *head, forward);
+    }
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, geometry.positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, geometry.colors);
+    mesh.insert_indices(Indices::U32(geometry.indices));
+}
+
+fn attach_rope_meshes(
+    mut commands: Commands,
+    ropes: Query<(Entity, &Rope, Option<&RopeStyle>), Added<Rope>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let default_style = RopeStyle::default();
+    for (entity, rope, style) in ropes.iter() {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        write_mesh(&mut mesh, rope, style.unwrap_or(&default_style), 0.0);
+        // Vertex colors carry the rope color, the material only passes them through
+        commands.entity(entity).insert((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(mesh)),
+                material: materials.add(ColorMaterial::from(Color::WHITE)),
+                ..default()
+            },
+            // The bounds computed for the first shape go stale as the rope moves
+            NoFrustumCulling,
+        ));
+    }
+}
+
+type RopeRedraw = Or<(Changed<Rope>, Changed<RopeStyle>, Changed<RopeFlash>)>;
+type DrawnRope<'a> = (
+    &'a Rope,
+    Option<&'a RopeStyle>,
+    Option<&'a RopeFlash>,
+    &'a Mesh2dHandle,
+);
+
+fn update_rope_meshes(ropes: Query<DrawnRope, RopeRedraw>, mut meshes: ResMut<Assets<Mesh>>) {
+    let default_style = RopeStyle::default();
+    for (rope, style, flash, handle) in ropes.iter() {
+        let flash = flash.map_or(0.0, |flash| 1.0 - flash.0.fraction());
+        if let Some(mesh) = meshes.get_mut(&handle.0) {
+            write_mesh(mesh, rope, style.unwrap_or(&default_style), flash);
+        }
+    }
+}
+
+fn start_damage_flash(
+    mut commands: Commands,
+    mut damaged: EventReader<RopeDamaged>,
+    ropes: Query<Option<&RopeStyle>, With<Rope>>,
+) {
+    for event in damaged.read() {
+        if let Ok(style) = ropes.get(event.rope) {
+            let seconds = style.map_or(RopeStyle::default().flash_seconds, |style| {
+                style.flash_seconds
+            });
+            commands
+                .entity(event.rope)
+                .insert(RopeFlash(Timer::from_seconds(seconds, TimerMode::Once)));
+        }
+    }
+}
+
+fn fade_damage_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut RopeFlash)>,
+) {
+    for (entity, mut flash) in flashes.iter_mut() {
+        flash.0.tick(time.delta());
+        if flash.0.finished() {
+            commands.entity(entity).remove::<RopeFlash>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+    use crate::rope::RopeBuilder;
+
+    fn point(x: f32, y: f32) -> StrokePoint {
+        StrokePoint {
+            position: Vec2::new(x, y),
+            width: 4.0,
+            color: Vec4::ONE,
+        }
+    }
+
+    fn rope(count: usize) -> Rope {
+        RopeBuilder::straight(Vec2::ZERO, Vec2::new(10.0 * (count as f32 - 1.0), 0.0))
+            .points(count)
+            .thickness(4.0)
+            .build(&GameConfig::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn stroke_points_apply_gradient_and_taper() {
+        let style = RopeStyle {
+            head_color: Color::WHITE,
+            tail_color: Color::BLACK,
+            taper: 0.5,
+            ..default()
+        };
+        let points = stroke_points(&rope(3), &style, 0.0);
+        assert_eq!(points[0].width, 4.0);
+        assert_eq!(points[1].width, 3.0);
+        assert_eq!(points[2].width, 2.0);
+        assert_eq!(points[0].color, Vec4::ONE);
+        assert_eq!(points[2].color, Vec4::new(0.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn stroke_points_flash_towards_flash_color() {
+        let style = RopeStyle {
+            flash_color: Color::RED,
+            ..default()
+        };
+        let points = stroke_points(&rope(3), &style, 1.0);
+        assert!(points
+            .iter()
+            .all(|point| point.color == Vec4::new(1.0, 0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn diamond_head_points_away_from_the_rope() {
+        let mut geometry = StrokeGeometry::default();
+        geometry.push_head(
+            HeadShape::Diamond { scale: 2.0 },
+            point(0.0, 0.0),
+            Vec2::NEG_X,
+        );
+        assert_eq!(geometry.positions.len(), 4);
+        assert_eq!(geometry.positions[0], [-4.0, 0.0, 0.0]);
+        assert_eq!(geometry.indices.len(), 6);
+    }
+
+    #[test]
+    fn smooth_passes_through_every_point() {
+        let points = [point(0.0, 0.0), point(10.0, 5.0), point(20.0, 0.0)];
+        let samples = smooth(&points, 4);
+        assert_eq!(samples.len(), 9);
+        assert_eq!(samples[0].position, points[0].position);
+        assert!(samples[4].position.distance(points[1].position) < 1e-4);
+        assert_eq!(samples[8].position, points[2].position);
+    }
+
+    #[test]
+    fn smooth_interpolates_width() {
+        let mut points = [point(0.0, 0.0), point(10.0, 0.0)];
+        points[1].width = 8.0;
+        let samples = smooth(&points, 2);
+        assert_eq!(samples[1].width, 6.0);
+    }
+
+    #[test]
+    fn tessellate_builds_a_strip_with_two_caps() {
+        let geometry = tessellate(&[point(0.0, 0.0), point(10.0, 0.0), point(20.0, 0.0)]);
+        let cap_vertices = 2 * (CAP_SEGMENTS + 2);
+        assert_eq!(geometry.positions.len(), 6 + cap_vertices);
+        assert_eq!(geometry.indices.len(), 2 * 6 + 2 * CAP_SEGMENTS * 3);
+        // Edges sit half the width either side of the center line
+        assert_eq!(geometry.positions[0], [0.0, 2.0, 0.0]);
+        assert_eq!(geometry.positions[1], [0.0, -2.0, 0.0]);
+        // The head cap bulges away from the rest of the rope
+        let head_cap = &geometry.positions[6..6 + CAP_SEGMENTS + 2];
+        assert!(head_cap.iter().all(|vertex| vertex[0] <= 1e-4));
+        assert!(head_cap.iter().any(|vertex| vertex[0] < -1.9));
+    }
+
+    #[test]
+    fn tessellate_handles_coincident_points() {
+        let geometry = tessellate(&[point(5.0, 5.0), point(5.0, 5.0), point(5.0, 5.0)]);
+        assert!(geometry.positions.iter().flatten().all(|v| v.is_finite()));
+        assert!(geometry
+            .indices
+            .iter()
+            .all(|index| (*index as usize) < geometry.positions.len()));
+        assert!(tessellate(&[]).positions.is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+
+use crate::obstacle::{ArenaWalls, Obstacle, ObstacleShape};
+
+// Draws obstacles and arena walls as lyon shapes
+pub struct ShapesPlugin;
+
+impl Plugin for ShapesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ShapePlugin).add_systems(
+            Update,
+            (
+                add_obstacle_shapes,
+                update_walls_outline.run_if(resource_changed_or_removed::<ArenaWalls>()),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct ArenaWallsOutline;
+
+fn add_obstacle_shapes(
+    mut commands: Commands,
+    obstacles: Query<(Entity, &Obstacle), Added<Obstacle>>,
+) {
+    for (entity, obstacle) in obstacles.iter() {
+        let path = match &obstacle.shape {
+            ObstacleShape::Circle { radius } => GeometryBuilder::build_as(&shapes::Circle {
+                radius: *radius,
+                center: Vec2::ZERO,
+            }),
+            ObstacleShape::Box { half_extents } => GeometryBuilder::build_as(&shapes::Rectangle {
+                extents: *half_extents * 2.0,
+                origin: shapes::RectangleOrigin::Center,
+            }),
+            ObstacleShape::Polygon { points } => GeometryBuilder::build_as(&shapes::Polygon {
+                points: points.clone(),
+                closed: true,
+            }),
+        };
+        commands.entity(entity).insert((
+            ShapeBundle {
+                path,
+                spatial: SpatialBundle::from_transform(Transform::from_xyz(
+                    obstacle.position.x,
+                    obstacle.position.y,
+                    -1.0,
+                )),
+                ..default()
+            },
+            Fill::color(Color::DARK_GRAY),
+            Stroke::new(Color::GRAY, 2.0),
+        ));
+    }
+}
+
+fn update_walls_outline(
+    mut commands: Commands,
+    walls: Option<Res<ArenaWalls>>,
+    outlines: Query<Entity, With<ArenaWallsOutline>>,
+) {
+    for entity in outlines.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(walls) = walls else {
+        return;
+    };
+    let mut path_builder = PathBuilder::new();
+    path_builder.move_to(walls.bounds.min);
+    path_builder.line_to(Vec2::new(walls.bounds.max.x, walls.bounds.min.y));
+    path_builder.line_to(walls.bounds.max);
+    path_builder.line_to(Vec2::new(walls.bounds.min.x, walls.bounds.max.y));
+    path_builder.close();
+    commands.spawn((
+        ShapeBundle {
+            path: path_builder.build(),
+            spatial: SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.0, -1.0)),
+            ..default()
+        },
+        Stroke::new(Color::GRAY, 4.0),
+        ArenaWallsOutline,
+    ));
+}
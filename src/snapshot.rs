@@ -0,0 +1,219 @@
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::scene::serde::SceneDeserializer;
+use bevy::scene::SceneSpawnError;
+use serde::de::DeserializeSeed;
+use thiserror::Error;
+
+use crate::arena::{arena_settled, ApplyArenaSet, WaveState};
+use crate::config::GameConfig;
+use crate::game::{
+    DetachedRope, Enemy, EnemySpawnTimer, Food, FoodSpawnTimer, GameOver, Health, MousePosition,
+    Score,
+};
+use crate::highscore::RunStats;
+use crate::rng::{GameRng, RngState};
+use crate::rope::{Rope, RopeStyle};
+use crate::storage::{platform_storage, TextStorage};
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("could not serialize run: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("could not parse run: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not restore run: {0}")]
+    Spawn(#[from] SceneSpawnError),
+    #[error("saved rope is malformed")]
+    InvalidRope,
+}
+
+type RunEntities = Or<(With<Rope>, With<Enemy>, With<Food>)>;
+
+// The run in progress as a reflected scene: ropes, enemies, food, the spawn timers, wave
+// progress, the run's stats, the cursor and the RNG position. Arena geometry is not
+// included; it comes back from the arena itself.
+pub fn run_scene(world: &mut World) -> DynamicScene {
+    let rng = world.resource::<GameRng>();
+    let state = RngState {
+        seed: rng.seed,
+        word_pos: rng.rng.get_word_pos() as u64,
+    };
+    world.insert_resource(state);
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, RunEntities>()
+        .iter(world)
+        .collect();
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .allow::<Rope>()
+        .allow::<RopeStyle>()
+        .allow::<DetachedRope>()
+        .allow::<Score>()
+        .allow::<Health>()
+        .allow::<Enemy>()
+        .allow::<Food>()
+        .allow_resource::<EnemySpawnTimer>()
+        .allow_resource::<FoodSpawnTimer>()
+        .allow_resource::<WaveState>()
+        .allow_resource::<RunStats>()
+        .allow_resource::<MousePosition>()
+        .allow_resource::<RngState>()
+        .extract_entities(entities.iter().copied())
+        .extract_resources()
+        .build();
+    // Entities are restored in scene order. Keep the order they are iterated in now, as
+    // collision resolution depends on it.
+    scene
+        .entities
+        .sort_by_key(|entry| entities.iter().position(|entity| *entity == entry.entity));
+    world.remove_resource::<RngState>();
+    scene
+}
+
+pub fn save_snapshot(world: &mut World) -> Result<String, SnapshotError> {
+    let scene = run_scene(world);
+    let text = scene.serialize_ron(&world.resource::<AppTypeRegistry>().0)?;
+    Ok(text)
+}
+
+// Replaces the ropes, enemies and food in play with a saved run
+pub fn restore_snapshot(world: &mut World, text: &str) -> Result<(), SnapshotError> {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let scene = {
+        let registry = registry.read();
+        let mut deserializer = ron::de::Deserializer::from_str(text)?;
+        SceneDeserializer {
+            type_registry: &registry,
+        }
+        .deserialize(&mut deserializer)
+        .map_err(|err| deserializer.span_error(err))?
+    };
+    // Checked before anything is despawned, so a bad run leaves the current one in place
+    let ropes = scene
+        .entities
+        .iter()
+        .flat_map(|entity| entity.components.iter())
+        .filter(|component| component.represents::<Rope>());
+    for component in ropes {
+        if !Rope::from_reflect(component.as_reflect()).is_some_and(|rope| valid_rope(&rope)) {
+            return Err(SnapshotError::InvalidRope);
+        }
+    }
+    let current: Vec<Entity> = world
+        .query_filtered::<Entity, RunEntities>()
+        .iter(world)
+        .collect();
+    for entity in current {
+        world.despawn(entity);
+    }
+    scene.write_to_world(world, &mut EntityHashMap::default())?;
+    if let Some(state) = world.remove_resource::<RngState>() {
+        let mut rng = world.resource_mut::<GameRng>();
+        rng.seed = state.seed;
+        rng.reseed();
+        rng.rng.set_word_pos(state.word_pos as u128);
+    }
+    Ok(())
+}
+
+// Every rope system expects a segment and a previous position for each point
+fn valid_rope(rope: &Rope) -> bool {
+    rope.points.len() >= 2
+        && rope.points.len() == rope.prev_points.len()
+        && rope
+            .points
+            .iter()
+            .chain(rope.prev_points.iter())
+            .all(|point| point.is_finite())
+}
+
+// Sent to store the run in progress, e.g. when the game is paused
+#[derive(Event)]
+pub struct SaveSnapshot;
+
+#[derive(Resource)]
+pub struct SnapshotStorage(pub Box<dyn TextStorage>);
+
+impl Default for SnapshotStorage {
+    fn default() -> Self {
+        SnapshotStorage(platform_storage("run"))
+    }
+}
+
+// A stored run waiting for the arena before it is restored
+#[derive(Resource)]
+struct PendingSnapshot(String);
+
+// Saves the run when a SaveSnapshot is sent, such as when the game pauses, and resumes
+// a stored run on the next start. A run that ended is forgotten.
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SnapshotStorage>()
+            .add_event::<SaveSnapshot>()
+            .add_systems(Startup, load_pending_snapshot)
+            .add_systems(
+                Update,
+                (
+                    resume_run
+                        .after(ApplyArenaSet)
+                        .run_if(resource_exists::<PendingSnapshot>)
+                        .run_if(arena_settled),
+                    save_run.run_if(on_event::<SaveSnapshot>()),
+                    forget_ended_run,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn load_pending_snapshot(
+    mut commands: Commands,
+    storage: Res<SnapshotStorage>,
+    config: Res<GameConfig>,
+) {
+    // Replays start from the seed, not from wherever the last run stopped
+    if config.replay.is_some() || config.record.is_some() {
+        return;
+    }
+    if let Some(text) = storage.0.load() {
+        commands.insert_resource(PendingSnapshot(text));
+    }
+}
+
+fn resume_run(world: &mut World) {
+    let Some(PendingSnapshot(text)) = world.remove_resource::<PendingSnapshot>() else {
+        return;
+    };
+    match restore_snapshot(world, &text) {
+        Ok(()) => info!("resumed saved run"),
+        Err(err) => warn!("starting a new run: {err}"),
+    }
+}
+
+fn save_run(world: &mut World) {
+    // Nothing to resume once every rope is gone
+    let in_play = world
+        .query_filtered::<(), (With<Rope>, With<Health>)>()
+        .iter(world)
+        .next()
+        .is_some();
+    if !in_play {
+        return;
+    }
+    let result = save_snapshot(world)
+        .map_err(|err| err.to_string())
+        .and_then(|text| world.resource::<SnapshotStorage>().0.save(&text));
+    if let Err(err) = result {
+        error!("could not save run: {err}");
+    }
+}
+
+fn forget_ended_run(mut game_over: EventReader<GameOver>, storage: Res<SnapshotStorage>) {
+    if game_over.read().count() > 0 {
+        if let Err(err) = storage.0.clear() {
+            error!("could not clear saved run: {err}");
+        }
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// Somewhere to keep a piece of text between sessions, such as high scores or a saved run
+pub trait TextStorage: Send + Sync + 'static {
+    fn load(&self) -> Option<String>;
+    fn save(&self, text: &str) -> Result<(), String>;
+    fn clear(&self) -> Result<(), String>;
+}
+
+// Local storage for the platform: a file in the user data directory on native,
+// localStorage in the browser, and memory on wasm without the `wasm` feature
+#[cfg(not(target_arch = "wasm32"))]
+pub fn platform_storage(name: &'static str) -> Box<dyn TextStorage> {
+    Box::new(FileStorage::in_user_data_dir(&format!("{name}.ron")))
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub fn platform_storage(name: &'static str) -> Box<dyn TextStorage> {
+    Box::new(LocalStorage {
+        key: format!("web-game.{name}"),
+    })
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+pub fn platform_storage(_name: &'static str) -> Box<dyn TextStorage> {
+    Box::new(MemoryStorage::default())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    pub path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    // e.g. ~/.local/share/web-game/scores.ron; the working directory if none is known
+    pub fn in_user_data_dir(file_name: &str) -> Self {
+        let dir = user_data_dir().map_or_else(PathBuf::new, |dir| dir.join("web-game"));
+        FileStorage {
+            path: dir.join(file_name),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn user_data_dir() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local/share")))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TextStorage for FileStorage {
+    fn load(&self) -> Option<String> {
+        std::fs::read_to_string(&self.path).ok()
+    }
+
+    fn save(&self, text: &str) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        std::fs::write(&self.path, text).map_err(|err| format!("{}: {err}", self.path.display()))
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        match std::fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("{}: {err}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// The browser's localStorage under a single key
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub struct LocalStorage {
+    pub key: String,
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+impl LocalStorage {
+    fn storage() -> Result<web_sys::Storage, String> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| "localStorage is unavailable".to_string())
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+impl TextStorage for LocalStorage {
+    fn load(&self) -> Option<String> {
+        LocalStorage::storage().ok()?.get_item(&self.key).ok()?
+    }
+
+    fn save(&self, text: &str) -> Result<(), String> {
+        LocalStorage::storage()?
+            .set_item(&self.key, text)
+            .map_err(|err| format!("{err:?}"))
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        LocalStorage::storage()?
+            .remove_item(&self.key)
+            .map_err(|err| format!("{err:?}"))
+    }
+}
+
+// Kept for the session only; used in tests and where nothing else is available
+#[derive(Default)]
+pub struct MemoryStorage {
+    text: Mutex<Option<String>>,
+}
+
+impl TextStorage for MemoryStorage {
+    fn load(&self) -> Option<String> {
+        self.text.lock().ok()?.clone()
+    }
+
+    fn save(&self, text: &str) -> Result<(), String> {
+        let mut stored = self.text.lock().map_err(|err| err.to_string())?;
+        *stored = Some(text.to_string());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        let mut stored = self.text.lock().map_err(|err| err.to_string())?;
+        *stored = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_storage_creates_its_directory_and_clears() {
+        let dir = std::env::temp_dir().join(format!("web-game-storage-{}", std::process::id()));
+        let storage = FileStorage {
+            path: dir.join("nested").join("scores.ron"),
+        };
+        assert_eq!(storage.load(), None);
+        storage.save("(best_score: 3)").unwrap();
+        assert_eq!(storage.load().as_deref(), Some("(best_score: 3)"));
+        storage.clear().unwrap();
+        assert_eq!(storage.load(), None);
+        // Clearing again is not an error
+        storage.clear().unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
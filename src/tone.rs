@@ -0,0 +1,169 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use bevy::audio::Source;
+use bevy::prelude::*;
+
+const SAMPLE_RATE: u32 = 44_100;
+// Seconds each note fades in over, so notes start without a click
+const ATTACK: f32 = 0.005;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Wave {
+    Sine,
+    Square,
+    Triangle,
+}
+
+impl Wave {
+    // One period over `phase` 0..1, in -1..1
+    fn sample(self, phase: f32) -> f32 {
+        match self {
+            Wave::Sine => (phase * TAU).sin(),
+            Wave::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Wave::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+        }
+    }
+}
+
+// A note that glides from one frequency to another and fades out over its length
+#[derive(Clone, Copy, Debug)]
+pub struct Note {
+    pub wave: Wave,
+    pub from_hz: f32,
+    pub to_hz: f32,
+    pub seconds: f32,
+}
+
+impl Note {
+    pub fn new(wave: Wave, hz: f32, seconds: f32) -> Self {
+        Note::glide(wave, hz, hz, seconds)
+    }
+
+    pub fn glide(wave: Wave, from_hz: f32, to_hz: f32, seconds: f32) -> Self {
+        Note {
+            wave,
+            from_hz,
+            to_hz,
+            seconds,
+        }
+    }
+
+    pub fn rest(seconds: f32) -> Self {
+        Note::new(Wave::Sine, 0.0, seconds)
+    }
+
+    fn samples(&self) -> usize {
+        (self.seconds * SAMPLE_RATE as f32) as usize
+    }
+}
+
+// A synthesized sound: its notes played one after another at `gain`
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct Tone {
+    pub notes: Vec<Note>,
+    pub gain: f32,
+}
+
+impl Tone {
+    pub fn new(gain: f32, notes: Vec<Note>) -> Self {
+        Tone { notes, gain }
+    }
+}
+
+impl Decodable for Tone {
+    type DecoderItem = f32;
+    type Decoder = ToneDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        ToneDecoder {
+            tone: self.clone(),
+            note: 0,
+            sample: 0,
+            phase: 0.0,
+        }
+    }
+}
+
+pub struct ToneDecoder {
+    tone: Tone,
+    note: usize,
+    // Sample within the current note
+    sample: usize,
+    phase: f32,
+}
+
+impl Iterator for ToneDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let note = loop {
+            let note = self.tone.notes.get(self.note)?;
+            if self.sample < note.samples() {
+                break note;
+            }
+            self.note += 1;
+            self.sample = 0;
+            self.phase = 0.0;
+        };
+        let t = self.sample as f32 / SAMPLE_RATE as f32;
+        let progress = t / note.seconds;
+        let hz = note.from_hz + (note.to_hz - note.from_hz) * progress;
+        let envelope = (t / ATTACK).min(1.0) * (1.0 - progress);
+        let value = note.wave.sample(self.phase) * envelope * self.tone.gain;
+        self.phase = (self.phase + hz / SAMPLE_RATE as f32).fract();
+        self.sample += 1;
+        Some(value)
+    }
+}
+
+impl Source for ToneDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let seconds = self.tone.notes.iter().map(|note| note.seconds).sum();
+        Some(Duration::from_secs_f32(seconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoder_plays_each_note_then_ends() {
+        let tone = Tone::new(
+            0.5,
+            vec![
+                Note::new(Wave::Square, 440.0, 0.1),
+                Note::rest(0.05),
+                Note::glide(Wave::Triangle, 880.0, 220.0, 0.1),
+            ],
+        );
+        let samples: Vec<f32> = tone.decoder().collect();
+        let expected = tone.notes.iter().map(Note::samples).sum::<usize>();
+        assert_eq!(samples.len(), expected);
+        assert!(samples.iter().all(|sample| sample.abs() <= 0.5));
+        // Each note fades in from silence
+        assert_eq!(samples[0], 0.0);
+        let rest = tone.notes[0].samples()..tone.notes[0].samples() + tone.notes[1].samples();
+        assert!(samples[rest].iter().all(|sample| *sample == 0.0));
+        assert!(samples.iter().any(|sample| sample.abs() > 0.25));
+    }
+}
@@ -0,0 +1,426 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::arena::{ActiveArena, WaveState};
+use crate::config::GameConfig;
+use crate::game::{DetachedRope, Enemy, EnemySpawnTimer, FoodSpawnTimer};
+use crate::rope::Rope;
+
+const TRACK_WIDTH: f32 = 160.0;
+
+// Developer panel with sliders for the main GameConfig tuning values, toggled with F2.
+// Changes apply to the live ropes, enemies and spawn timers, and "Export" writes the
+// current config so tuned values can be committed.
+pub struct TuningPanelPlugin;
+
+impl Plugin for TuningPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Tuned>()
+            .add_systems(Startup, setup_panel)
+            .add_systems(
+                Update,
+                (
+                    toggle_panel,
+                    drag_sliders,
+                    apply_tuning,
+                    update_sliders.run_if(resource_changed::<GameConfig>),
+                    export_config,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Param {
+    RopeElasticity,
+    RopeThickness,
+    RopeDamping,
+    EnemyDamping,
+    EnemyAcceleration,
+    EnemySpawnInterval,
+    FoodSpawnInterval,
+}
+
+impl Param {
+    const ALL: [Param; 7] = [
+        Param::RopeElasticity,
+        Param::RopeThickness,
+        Param::RopeDamping,
+        Param::EnemyDamping,
+        Param::EnemyAcceleration,
+        Param::EnemySpawnInterval,
+        Param::FoodSpawnInterval,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Param::RopeElasticity => "Rope elasticity",
+            Param::RopeThickness => "Rope thickness",
+            Param::RopeDamping => "Rope damping",
+            Param::EnemyDamping => "Enemy damping",
+            Param::EnemyAcceleration => "Enemy acceleration",
+            Param::EnemySpawnInterval => "Enemy spawn interval",
+            Param::FoodSpawnInterval => "Food spawn interval",
+        }
+    }
+
+    // Both ends of a segment move by its error over the elasticity, so below 2 the
+    // constraint solver overshoots the rest length; damping of 1 is none
+    fn range(self) -> (f32, f32) {
+        match self {
+            Param::RopeElasticity => (2.0, 20.0),
+            Param::RopeThickness => (1.0, 20.0),
+            Param::RopeDamping | Param::EnemyDamping => (1.0, 1.1),
+            Param::EnemyAcceleration => (0.001, 0.05),
+            Param::EnemySpawnInterval | Param::FoodSpawnInterval => (0.1, 5.0),
+        }
+    }
+
+    pub fn get(self, config: &GameConfig) -> f32 {
+        match self {
+            Param::RopeElasticity => config.rope_elasticity,
+            Param::RopeThickness => config.rope_thickness,
+            Param::RopeDamping => config.rope_damping,
+            Param::EnemyDamping => config.enemy_damping,
+            Param::EnemyAcceleration => config.enemy_acceleration,
+            Param::EnemySpawnInterval => config.enemy_spawn_interval,
+            Param::FoodSpawnInterval => config.food_spawn_interval,
+        }
+    }
+
+    pub fn set(self, config: &mut GameConfig, value: f32) {
+        let (min, max) = self.range();
+        let value = value.clamp(min, max);
+        match self {
+            Param::RopeElasticity => config.rope_elasticity = value,
+            Param::RopeThickness => config.rope_thickness = value,
+            Param::RopeDamping => config.rope_damping = value,
+            Param::EnemyDamping => config.enemy_damping = value,
+            Param::EnemyAcceleration => config.enemy_acceleration = value,
+            Param::EnemySpawnInterval => config.enemy_spawn_interval = value,
+            Param::FoodSpawnInterval => config.food_spawn_interval = value,
+        }
+    }
+
+    // Position of the value along the slider track, from 0 to 1
+    fn fraction(self, config: &GameConfig) -> f32 {
+        let (min, max) = self.range();
+        ((self.get(config) - min) / (max - min)).clamp(0.0, 1.0)
+    }
+}
+
+// Sent when a slider moves a value that is copied into entities or timers at spawn
+#[derive(Event)]
+pub struct Tuned(pub Param);
+
+#[derive(Component)]
+pub struct TuningPanel;
+
+#[derive(Component)]
+struct Slider(Param);
+
+#[derive(Component)]
+struct SliderFill(Param);
+
+#[derive(Component)]
+struct SliderLabel(Param);
+
+#[derive(Component)]
+struct ExportButton;
+
+fn slider_text(param: Param, config: &GameConfig) -> String {
+    format!("{}: {:.3}", param.label(), param.get(config))
+}
+
+fn setup_panel(mut commands: Commands, config: Res<GameConfig>) {
+    let text_style = TextStyle {
+        font_size: 14.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(60.0),
+                    left: Val::Px(5.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            TuningPanel,
+        ))
+        .with_children(|panel| {
+            for param in Param::ALL {
+                panel.spawn((
+                    TextBundle::from_section(slider_text(param, &config), text_style.clone()),
+                    SliderLabel(param),
+                ));
+                panel
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(TRACK_WIDTH),
+                                height: Val::Px(10.0),
+                                ..default()
+                            },
+                            background_color: Color::DARK_GRAY.into(),
+                            ..default()
+                        },
+                        Slider(param),
+                    ))
+                    .with_children(|track| {
+                        track.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(param.fraction(&config) * 100.0),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                background_color: Color::ORANGE.into(),
+                                ..default()
+                            },
+                            SliderFill(param),
+                        ));
+                    });
+            }
+            panel
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(6.0)),
+                            padding: UiRect::all(Val::Px(4.0)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        background_color: Color::GRAY.into(),
+                        ..default()
+                    },
+                    ExportButton,
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        "Export config",
+                        text_style.clone(),
+                    ));
+                });
+        });
+}
+
+fn toggle_panel(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut panels: Query<&mut Visibility, With<TuningPanel>>,
+) {
+    if !keys.just_pressed(KeyCode::F2) {
+        return;
+    }
+    for mut visibility in panels.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn drag_sliders(
+    sliders: Query<(&Interaction, &Node, &GlobalTransform, &Slider)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut config: ResMut<GameConfig>,
+    mut tuned: EventWriter<Tuned>,
+) {
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+    for (interaction, node, transform, slider) in sliders.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // UI transforms hold the node center in logical pixels, like the cursor
+        let width = node.size().x.max(1.0);
+        let left = transform.translation().x - width / 2.0;
+        let fraction = ((cursor.x - left) / width).clamp(0.0, 1.0);
+        let (min, max) = slider.0.range();
+        let value = min + (max - min) * fraction;
+        if slider.0.get(&config) != value {
+            slider.0.set(&mut config, value);
+            tuned.send(Tuned(slider.0));
+        }
+    }
+}
+
+// Pushes tuned values into what was already spawned; damping is read from the config
+// every tick and needs nothing here. Spawn values the current wave or food table sets
+// itself are left alone, as spawning keeps using them.
+#[allow(clippy::too_many_arguments)]
+fn apply_tuning(
+    mut tuned: EventReader<Tuned>,
+    config: Res<GameConfig>,
+    arena: Res<ActiveArena>,
+    wave_state: Res<WaveState>,
+    mut ropes: Query<&mut Rope, Without<DetachedRope>>,
+    mut enemies: Query<&mut Enemy>,
+    mut enemy_timer: ResMut<EnemySpawnTimer>,
+    mut food_timer: ResMut<FoodSpawnTimer>,
+) {
+    let wave = wave_state
+        .current
+        .and_then(|index| arena.0.waves.get(index));
+    for Tuned(param) in tuned.read() {
+        match param {
+            Param::RopeElasticity => {
+                for mut rope in ropes.iter_mut() {
+                    rope.elasticity = config.rope_elasticity;
+                }
+            }
+            Param::RopeThickness => {
+                for mut rope in ropes.iter_mut() {
+                    rope.thickness = config.rope_thickness;
+                }
+            }
+            Param::EnemyAcceleration => {
+                if wave.is_some_and(|wave| wave.acceleration.is_some()) {
+                    continue;
+                }
+                for mut enemy in enemies.iter_mut() {
+                    enemy.acceleration = config.enemy_acceleration;
+                }
+            }
+            Param::EnemySpawnInterval => {
+                if wave.is_some_and(|wave| wave.interval.is_some()) {
+                    continue;
+                }
+                let interval = Duration::from_secs_f32(config.enemy_spawn_interval);
+                enemy_timer.0.set_duration(interval);
+            }
+            Param::FoodSpawnInterval => {
+                if arena.0.food.interval.is_some() {
+                    continue;
+                }
+                let interval = Duration::from_secs_f32(config.food_spawn_interval);
+                food_timer.0.set_duration(interval);
+            }
+            Param::RopeDamping | Param::EnemyDamping => {}
+        }
+    }
+}
+
+fn update_sliders(
+    config: Res<GameConfig>,
+    mut fills: Query<(&mut Style, &SliderFill)>,
+    mut labels: Query<(&mut Text, &SliderLabel)>,
+) {
+    for (mut style, fill) in fills.iter_mut() {
+        style.width = Val::Percent(fill.0.fraction(&config) * 100.0);
+    }
+    for (mut text, label) in labels.iter_mut() {
+        text.sections[0].value = slider_text(label.0, &config);
+    }
+}
+
+fn export_config(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<ExportButton>)>,
+    config: Res<GameConfig>,
+) {
+    if !buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let config = config.tuning();
+    #[cfg(not(target_arch = "wasm32"))]
+    match config.save() {
+        Ok(()) => info!("exported tuned config"),
+        Err(err) => error!("could not export config: {err}"),
+    }
+    // The browser has no config file; log it to be copied from the console instead
+    #[cfg(target_arch = "wasm32")]
+    match config.to_ron() {
+        Ok(text) => info!("tuned config:\n{text}"),
+        Err(err) => error!("could not export config: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+
+    #[test]
+    fn set_clamps_to_slider_range() {
+        let mut config = GameConfig::default();
+        for param in Param::ALL {
+            let (min, max) = param.range();
+            param.set(&mut config, max + 1.0);
+            assert_eq!(param.get(&config), max);
+            assert_eq!(param.fraction(&config), 1.0);
+            param.set(&mut config, min - 1.0);
+            assert_eq!(param.get(&config), min);
+            assert_eq!(param.fraction(&config), 0.0);
+        }
+    }
+
+    #[test]
+    fn tuning_leaves_arena_overrides_alone() {
+        let mut arena = Arena::default();
+        arena.waves[0].interval = Some(1.5);
+        arena.waves[0].acceleration = Some(0.012);
+        let mut app = App::new();
+        app.insert_resource(GameConfig {
+            enemy_spawn_interval: 3.0,
+            food_spawn_interval: 4.0,
+            enemy_acceleration: 0.03,
+            ..default()
+        })
+        .insert_resource(ActiveArena(arena))
+        .insert_resource(WaveState {
+            elapsed: 0.0,
+            current: Some(0),
+        })
+        .insert_resource(EnemySpawnTimer(Timer::from_seconds(
+            1.5,
+            TimerMode::Repeating,
+        )))
+        .insert_resource(FoodSpawnTimer(Timer::from_seconds(
+            1.0,
+            TimerMode::Repeating,
+        )))
+        .add_event::<Tuned>()
+        .add_systems(Update, apply_tuning);
+        let enemy = app.world.spawn(Enemy::new(Vec2::ZERO, 8.0, 0.012)).id();
+        for param in [
+            Param::EnemyAcceleration,
+            Param::EnemySpawnInterval,
+            Param::FoodSpawnInterval,
+        ] {
+            app.world.send_event(Tuned(param));
+        }
+        app.update();
+
+        assert_eq!(app.world.get::<Enemy>(enemy).unwrap().acceleration, 0.012);
+        let enemy_timer = &app.world.resource::<EnemySpawnTimer>().0;
+        assert_eq!(enemy_timer.duration().as_secs_f32(), 1.5);
+        // The food table has no interval of its own
+        let food_timer = &app.world.resource::<FoodSpawnTimer>().0;
+        assert_eq!(food_timer.duration().as_secs_f32(), 4.0);
+    }
+
+    #[test]
+    fn defaults_fit_slider_ranges() {
+        let config = GameConfig::default();
+        for param in Param::ALL {
+            let (min, max) = param.range();
+            let value = param.get(&config);
+            assert!((min..=max).contains(&value), "{param:?} = {value}");
+        }
+    }
+}
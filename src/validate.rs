@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::game::{Enemy, Food};
+use crate::rope::Rope;
+
+// Debug builds check the simulation after every tick and report any entity whose
+// position stopped being a finite number, since it never recovers on its own
+pub fn report_non_finite(
+    ropes: Query<(Entity, &Rope)>,
+    enemies: Query<(Entity, &Enemy)>,
+    food: Query<(Entity, &Food)>,
+    mut reported: Local<HashSet<Entity>>,
+) {
+    let mut report = |entity: Entity, kind: &str, values: &[Vec2]| {
+        if values.iter().all(|value| value.is_finite()) || !reported.insert(entity) {
+            return;
+        }
+        error!("{kind} {entity:?} has a non-finite position: {values:?}");
+    };
+    for (entity, rope) in ropes.iter() {
+        let values: Vec<Vec2> = rope
+            .points
+            .iter()
+            .chain(rope.prev_points.iter())
+            .copied()
+            .collect();
+        report(entity, "rope", &values);
+    }
+    for (entity, enemy) in enemies.iter() {
+        report(entity, "enemy", &[enemy.position, enemy.position_prev]);
+    }
+    for (entity, piece) in food.iter() {
+        report(entity, "food", &[piece.position]);
+    }
+    // Forget despawned entities
+    reported.retain(|entity| {
+        ropes.contains(*entity) || enemies.contains(*entity) || food.contains(*entity)
+    });
+}
@@ -0,0 +1,233 @@
+use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::audio::{AddAudioSource, Source};
+use bevy::prelude::*;
+
+use crate::audio::AudioUnlocked;
+use crate::config::GameConfig;
+use crate::game::DetachedRope;
+use crate::rope::Rope;
+
+const SAMPLE_RATE: u32 = 44_100;
+// Tail speeds, in pixels per tick, between which the whoosh fades in to full volume
+const QUIET_SPEED: f32 = 4.0;
+const LOUD_SPEED: f32 = 40.0;
+// Low-pass cutoff in Hz: slow swings are a dull rumble, fast whips a bright hiss
+const MIN_CUTOFF: f32 = 200.0;
+const MAX_CUTOFF: f32 = 5000.0;
+// Per-sample approach towards new levels, about 20ms, so tick updates do not click
+const SMOOTHING: f32 = 0.001;
+
+// A synthesized swish that follows the fastest rope tail: filtered noise whose volume
+// and brightness are set from the simulation each frame and read by the audio thread
+pub struct WhooshPlugin;
+
+impl Plugin for WhooshPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Whoosh>()
+            .add_systems(Startup, setup_whoosh)
+            .add_systems(
+                Update,
+                (
+                    start_whoosh.run_if(|unlocked: Res<AudioUnlocked>| unlocked.0),
+                    drive_whoosh,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Levels shared between the game and the decoder, stored as f32 bits
+#[derive(Default)]
+pub struct WhooshParams {
+    gain: AtomicU32,
+    cutoff: AtomicU32,
+}
+
+impl WhooshParams {
+    pub fn set(&self, gain: f32, cutoff: f32) {
+        self.gain.store(gain.to_bits(), Ordering::Relaxed);
+        self.cutoff.store(cutoff.to_bits(), Ordering::Relaxed);
+    }
+
+    fn get(&self) -> (f32, f32) {
+        (
+            f32::from_bits(self.gain.load(Ordering::Relaxed)),
+            f32::from_bits(self.cutoff.load(Ordering::Relaxed)),
+        )
+    }
+}
+
+#[derive(Asset, TypePath)]
+pub struct Whoosh {
+    params: Arc<WhooshParams>,
+}
+
+impl Decodable for Whoosh {
+    type DecoderItem = f32;
+    type Decoder = WhooshDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        WhooshDecoder::new(self.params.clone())
+    }
+}
+
+// Endless mono noise through two one-pole low-pass stages
+pub struct WhooshDecoder {
+    params: Arc<WhooshParams>,
+    noise: u32,
+    gain: f32,
+    cutoff: f32,
+    low: [f32; 2],
+}
+
+impl WhooshDecoder {
+    fn new(params: Arc<WhooshParams>) -> Self {
+        WhooshDecoder {
+            params,
+            noise: 0x9e37_79b9,
+            gain: 0.0,
+            cutoff: MIN_CUTOFF,
+            low: [0.0; 2],
+        }
+    }
+
+    // xorshift32 mapped to -1..1
+    fn white_noise(&mut self) -> f32 {
+        self.noise ^= self.noise << 13;
+        self.noise ^= self.noise >> 17;
+        self.noise ^= self.noise << 5;
+        self.noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+impl Iterator for WhooshDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let (gain, cutoff) = self.params.get();
+        self.gain += (gain - self.gain) * SMOOTHING;
+        self.cutoff += (cutoff - self.cutoff) * SMOOTHING;
+        let alpha = 1.0 - (-TAU * self.cutoff / SAMPLE_RATE as f32).exp();
+        let mut sample = self.white_noise();
+        for low in self.low.iter_mut() {
+            *low += alpha * (sample - *low);
+            sample = *low;
+        }
+        Some(sample * self.gain)
+    }
+}
+
+impl Source for WhooshDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+// Gain and cutoff for a tail moving at `speed`, before the volume setting
+fn whoosh_levels(speed: f32) -> (f32, f32) {
+    let intensity = ((speed - QUIET_SPEED) / (LOUD_SPEED - QUIET_SPEED)).clamp(0.0, 1.0);
+    (
+        intensity * intensity,
+        MIN_CUTOFF + (MAX_CUTOFF - MIN_CUTOFF) * intensity,
+    )
+}
+
+#[derive(Resource)]
+struct WhooshSound {
+    handle: Handle<Whoosh>,
+    params: Arc<WhooshParams>,
+}
+
+#[derive(Component)]
+struct WhooshPlayer;
+
+fn setup_whoosh(mut commands: Commands, mut whooshes: ResMut<Assets<Whoosh>>) {
+    let params = Arc::new(WhooshParams::default());
+    let handle = whooshes.add(Whoosh {
+        params: params.clone(),
+    });
+    commands.insert_resource(WhooshSound { handle, params });
+}
+
+fn start_whoosh(
+    mut commands: Commands,
+    sound: Res<WhooshSound>,
+    players: Query<(), With<WhooshPlayer>>,
+) {
+    if !players.is_empty() {
+        return;
+    }
+    commands.spawn((
+        AudioSourceBundle {
+            source: sound.handle.clone(),
+            settings: PlaybackSettings::LOOP,
+        },
+        WhooshPlayer,
+    ));
+}
+
+fn drive_whoosh(
+    sound: Res<WhooshSound>,
+    config: Res<GameConfig>,
+    virtual_time: Res<Time<Virtual>>,
+    ropes: Query<&Rope, Without<DetachedRope>>,
+) {
+    // A paused rope keeps its last tail speed, but should not keep whooshing
+    let speed = if virtual_time.is_paused() {
+        0.0
+    } else {
+        ropes.iter().map(Rope::tail_speed).fold(0.0, f32::max)
+    };
+    let (gain, cutoff) = whoosh_levels(speed);
+    let volume = if config.muted { 0.0 } else { config.sfx_volume };
+    sound.params.set(gain * volume, cutoff);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_tails_are_silent_and_fast_ones_bright() {
+        assert_eq!(whoosh_levels(0.0), (0.0, MIN_CUTOFF));
+        assert_eq!(whoosh_levels(QUIET_SPEED), (0.0, MIN_CUTOFF));
+        let (gain, cutoff) = whoosh_levels((QUIET_SPEED + LOUD_SPEED) / 2.0);
+        assert!(gain > 0.0 && gain < 1.0);
+        assert!(cutoff > MIN_CUTOFF && cutoff < MAX_CUTOFF);
+        assert_eq!(whoosh_levels(LOUD_SPEED * 2.0), (1.0, MAX_CUTOFF));
+    }
+
+    #[test]
+    fn decoder_fades_towards_the_shared_levels() {
+        let params = Arc::new(WhooshParams::default());
+        let mut decoder = WhooshDecoder::new(params.clone());
+        assert!(decoder.by_ref().take(100).all(|sample| sample == 0.0));
+
+        params.set(1.0, MAX_CUTOFF);
+        let samples: Vec<f32> = decoder.by_ref().take(SAMPLE_RATE as usize).collect();
+        assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+        assert!(samples[SAMPLE_RATE as usize / 2..]
+            .iter()
+            .any(|sample| sample.abs() > 0.05));
+
+        params.set(0.0, MIN_CUTOFF);
+        let tail = decoder.nth(SAMPLE_RATE as usize).unwrap();
+        assert!(tail.abs() < 1e-3);
+    }
+}
@@ -0,0 +1,295 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use web_game::arena::{arena_settled, ActiveArena, ArenaAssetPlugin};
+use web_game::config::GameConfig;
+use web_game::game::{DetachedRope, Enemy, Food, FoodCollected, GameOver, Health, Score};
+use web_game::headless::{set_cursor, step_ticks, HeadlessPlugin};
+use web_game::highscore::{HighScorePlugin, HighScoreStorage, RunStats};
+use web_game::replay::state_hash;
+use web_game::rope::{Rope, RopeBuilder};
+use web_game::snapshot::{
+    restore_snapshot, save_snapshot, SnapshotError, SnapshotPlugin, SnapshotStorage,
+};
+use web_game::storage::{MemoryStorage, TextStorage};
+use web_game::GamePlugin;
+
+// Fixed ticks per second of the default FixedUpdate timestep
+const TICKS_PER_SECOND: usize = 64;
+
+// A windowless app on the built-in arena; after the first step the rope is in play
+fn headless_app(seed: u64) -> App {
+    let mut app = App::new();
+    app.insert_resource(GameConfig {
+        seed: Some(seed),
+        ..default()
+    })
+    .add_plugins((MinimalPlugins, HeadlessPlugin, GamePlugin));
+    step_ticks(&mut app, 1);
+    app
+}
+
+fn rope_entity(app: &mut App) -> Option<Entity> {
+    app.world
+        .query_filtered::<Entity, (With<Rope>, Without<DetachedRope>)>()
+        .iter(&app.world)
+        .next()
+}
+
+fn enemy_count(app: &mut App) -> usize {
+    app.world.query::<&Enemy>().iter(&app.world).count()
+}
+
+fn sent<E: Event>(app: &App) -> usize {
+    let events = app.world.resource::<Events<E>>();
+    events.get_reader().read(events).count()
+}
+
+fn hash(app: &mut App) -> u64 {
+    app.world.run_system_once(
+        |ropes: Query<(&Rope, Option<&Score>, Option<&Health>)>,
+         enemies: Query<&Enemy>,
+         food: Query<&Food>| state_hash(&ropes, &enemies, &food),
+    )
+}
+
+#[test]
+fn run_starts_with_one_rope_and_no_enemies() {
+    let mut app = headless_app(1);
+    let rope = rope_entity(&mut app).expect("arena spawns a rope");
+    assert_eq!(app.world.get::<Score>(rope).unwrap().value, 0);
+    assert_eq!(app.world.get::<Health>(rope).unwrap().value, 5);
+    assert_eq!(enemy_count(&mut app), 0);
+}
+
+#[test]
+fn enemies_spawn_on_the_wave_interval() {
+    let mut app = headless_app(1);
+    let interval = GameConfig::default().enemy_spawn_interval;
+    let ticks = (interval * TICKS_PER_SECOND as f32) as usize;
+
+    step_ticks(&mut app, ticks - 2);
+    assert_eq!(enemy_count(&mut app), 0);
+    step_ticks(&mut app, 4);
+    assert_eq!(enemy_count(&mut app), 1);
+}
+
+#[test]
+fn enemies_outside_cull_distance_are_despawned() {
+    let mut app = headless_app(1);
+    let far = app
+        .world
+        .spawn(Enemy::new(Vec2::new(1000.0, 1000.0), 10.0, 0.0))
+        .id();
+    let near = app
+        .world
+        .spawn(Enemy::new(Vec2::new(0.0, 300.0), 10.0, 0.0))
+        .id();
+
+    step_ticks(&mut app, 1);
+
+    assert!(app.world.get_entity(far).is_none());
+    assert!(app.world.get_entity(near).is_some());
+}
+
+#[test]
+fn food_touching_the_rope_is_collected() {
+    let mut app = headless_app(1);
+    let rope = rope_entity(&mut app).unwrap();
+    let food = app
+        .world
+        .spawn(Food::new(Vec2::new(25.0, 0.0), 5.0, 3))
+        .id();
+
+    step_ticks(&mut app, 1);
+
+    assert!(app.world.get_entity(food).is_none());
+    assert_eq!(app.world.get::<Score>(rope).unwrap().value, 3);
+    assert_eq!(sent::<FoodCollected>(&app), 1);
+}
+
+#[test]
+fn enemy_reaching_the_head_costs_health() {
+    let mut app = headless_app(1);
+    let rope = rope_entity(&mut app).unwrap();
+    let enemy = app.world.spawn(Enemy::new(Vec2::ZERO, 5.0, 0.0)).id();
+
+    step_ticks(&mut app, 1);
+
+    assert!(app.world.get_entity(enemy).is_none());
+    let damage = GameConfig::default().enemy_damage;
+    assert_eq!(app.world.get::<Health>(rope).unwrap().value, 5 - damage);
+}
+
+#[test]
+fn rope_out_of_health_is_despawned_and_spawning_stops() {
+    let mut app = headless_app(1);
+    let rope = rope_entity(&mut app).unwrap();
+    app.world.get_mut::<Health>(rope).unwrap().value = 1;
+    app.world.spawn(Enemy::new(Vec2::ZERO, 5.0, 0.0));
+
+    assert_eq!(sent::<GameOver>(&app), 0);
+    step_ticks(&mut app, 1);
+    assert!(app.world.get_entity(rope).is_none());
+    assert_eq!(sent::<GameOver>(&app), 1);
+
+    step_ticks(&mut app, 5 * TICKS_PER_SECOND);
+    assert_eq!(enemy_count(&mut app), 0);
+}
+
+#[test]
+fn rope_head_follows_the_cursor() {
+    let mut app = headless_app(1);
+    set_cursor(&mut app, Vec2::new(40.0, -20.0));
+
+    step_ticks(&mut app, 1);
+
+    let rope = rope_entity(&mut app).unwrap();
+    assert_eq!(
+        app.world.get::<Rope>(rope).unwrap().points[0],
+        Vec2::new(40.0, -20.0)
+    );
+}
+
+#[test]
+fn same_seed_and_input_give_the_same_run() {
+    let run = |seed| {
+        let mut app = headless_app(seed);
+        for tick in 0..10 * TICKS_PER_SECOND {
+            let angle = tick as f32 / 40.0;
+            set_cursor(&mut app, Vec2::new(angle.cos(), angle.sin()) * 120.0);
+            step_ticks(&mut app, 1);
+        }
+        hash(&mut app)
+    };
+    assert_eq!(run(7), run(7));
+    assert_ne!(run(7), run(8));
+}
+
+#[test]
+fn restored_snapshot_continues_the_same_run() {
+    let mut original = headless_app(5);
+    for i in 0..300 {
+        set_cursor(&mut original, Vec2::from_angle(i as f32 * 0.05) * 120.0);
+        step_ticks(&mut original, 1);
+    }
+    let snapshot = save_snapshot(&mut original.world).unwrap();
+
+    // A different seed, so only the snapshot can make the runs match
+    let mut restored = headless_app(6);
+    restore_snapshot(&mut restored.world, &snapshot).unwrap();
+    assert_eq!(hash(&mut restored), hash(&mut original));
+
+    for i in 300..600 {
+        let cursor = Vec2::from_angle(i as f32 * 0.05) * 120.0;
+        set_cursor(&mut original, cursor);
+        set_cursor(&mut restored, cursor);
+        step_ticks(&mut original, 1);
+        step_ticks(&mut restored, 1);
+    }
+    assert!(enemy_count(&mut original) > 0);
+    assert_eq!(hash(&mut restored), hash(&mut original));
+}
+
+#[test]
+fn malformed_ropes_in_a_snapshot_are_rejected() {
+    let corruptions: [fn(&mut Rope); 3] = [
+        |rope| rope.points.clear(),
+        |rope| {
+            rope.prev_points.pop();
+        },
+        |rope| rope.points[1] = Vec2::NAN,
+    ];
+    for corrupt in corruptions {
+        let mut original = headless_app(5);
+        step_ticks(&mut original, 10);
+        let mut ropes = original.world.query::<&mut Rope>();
+        corrupt(&mut ropes.single_mut(&mut original.world));
+        let snapshot = save_snapshot(&mut original.world).unwrap();
+
+        let mut restored = headless_app(5);
+        step_ticks(&mut restored, 10);
+        let before = hash(&mut restored);
+        assert!(matches!(
+            restore_snapshot(&mut restored.world, &snapshot),
+            Err(SnapshotError::InvalidRope)
+        ));
+        // The run in play is left alone
+        assert_eq!(hash(&mut restored), before);
+    }
+}
+
+#[test]
+fn run_stats_reset_only_when_a_run_starts() {
+    let mut app = App::new();
+    app.insert_resource(GameConfig {
+        seed: Some(1),
+        ..default()
+    })
+    .insert_resource(HighScoreStorage(Box::new(MemoryStorage::default())))
+    .add_plugins((MinimalPlugins, HeadlessPlugin, GamePlugin, HighScorePlugin));
+    step_ticks(&mut app, 1);
+    app.world.resource_mut::<RunStats>().food_collected = 3;
+
+    // A rope joining the run in progress, as a restored one does, keeps the tally
+    let rope = RopeBuilder::straight(Vec2::ZERO, Vec2::new(100.0, 0.0))
+        .build(&GameConfig::default())
+        .unwrap();
+    app.world.spawn((rope, Score { value: 0 }, Health::new(5)));
+    step_ticks(&mut app, 1);
+    assert_eq!(app.world.resource::<RunStats>().food_collected, 3);
+
+    app.world.resource_mut::<ActiveArena>().set_changed();
+    step_ticks(&mut app, 1);
+    assert_eq!(app.world.resource::<RunStats>().food_collected, 0);
+}
+
+#[test]
+fn saved_run_resumes_with_its_stats_whether_the_arena_loads_or_not() {
+    let mut original = App::new();
+    original
+        .insert_resource(GameConfig {
+            seed: Some(3),
+            ..default()
+        })
+        .insert_resource(HighScoreStorage(Box::new(MemoryStorage::default())))
+        .add_plugins((MinimalPlugins, HeadlessPlugin, GamePlugin, HighScorePlugin));
+    step_ticks(&mut original, 100);
+    original.world.resource_mut::<RunStats>().food_collected = 3;
+    let snapshot = save_snapshot(&mut original.world).unwrap();
+
+    // The second folder does not exist, so the arena fails to load
+    for assets in ["assets", "missing"] {
+        let storage = MemoryStorage::default();
+        storage.save(&snapshot).unwrap();
+        let mut app = App::new();
+        app.insert_resource(HighScoreStorage(Box::new(MemoryStorage::default())))
+            .insert_resource(SnapshotStorage(Box::new(storage)))
+            .add_plugins((
+                MinimalPlugins,
+                AssetPlugin {
+                    file_path: assets.to_string(),
+                    watch_for_changes_override: Some(false),
+                    ..default()
+                },
+                HeadlessPlugin,
+                GamePlugin,
+                ArenaAssetPlugin,
+                HighScorePlugin,
+                SnapshotPlugin,
+            ));
+        for _ in 0..1000 {
+            app.update();
+            if app.world.run_system_once(arena_settled) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        // Nothing restarts the run once the arena is settled
+        step_ticks(&mut app, 2);
+        assert_eq!(
+            app.world.resource::<RunStats>().food_collected,
+            3,
+            "{assets}"
+        );
+    }
+}